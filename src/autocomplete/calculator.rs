@@ -0,0 +1,145 @@
+use crate::autocomplete::types::{
+	ActiveList,
+	CommandType,
+	Factory,
+	List,
+	Prompt,
+	State,
+};
+use crate::calculator::{ calculate, read_ans };
+
+pub struct CalculatorState {
+	ans: Option<f64>,
+	factory: Box<dyn Factory>,
+	preamble: String,
+	result: List, // the result in every format, or the error explaining why there is none
+	search: String,
+	selected: Option<usize>,
+	values: Vec<String>,
+}
+
+impl Default for CalculatorState {
+	fn default() -> Self {
+		CalculatorState {
+			ans: read_ans(),
+			factory: Box::new(CalculatorFactory),
+			preamble: String::from("="),
+			result: None,
+			search: String::default(),
+			selected: None,
+			values: Vec::new(),
+		}
+	}
+}
+
+impl CalculatorState {
+	fn select(&mut self, index: usize) -> (String, Option<String>) {
+		if let Some(result) = self.result.as_ref().and_then(|result| result.get(index)) {
+			self.selected = Some(index);
+			(self.search.clone(), Some(result.clone()))
+		} else {
+			(self.search.clone(), None)
+		}
+	}
+}
+
+impl State for CalculatorState {
+	fn get_factory(&self) -> &Box<dyn Factory> {
+		&self.factory
+	}
+
+	fn get_replacement(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_preamble(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_active_list(&self) -> ActiveList {
+		ActiveList::FuzzyFinder
+	}
+
+	fn get_autocomplete_list(&self) -> &List {
+		&None
+	}
+
+	fn get_fuzzyfinder_list(&self) -> &List {
+		&self.result
+	}
+
+	fn update_search(&mut self, search: String) {
+		self.search = search;
+		self.selected = None;
+
+		if self.search.trim().is_empty() {
+			self.result = None;
+			self.values = Vec::new();
+			return;
+		}
+
+		// errors are shown in the list instead of a result so mistakes are visible while typing
+		match calculate(&self.search, self.ans) {
+			Ok(values) => {
+				self.result = Some(values.iter().map(|value| format!("= {}", value)).collect());
+				self.values = values;
+			}
+			Err(error) => {
+				self.result = Some(vec![format!("error: {}", error)]);
+				self.values = Vec::new();
+			}
+		}
+	}
+
+	fn autocomplete(&mut self) -> (String, Option<String>) {
+		self.select(0)
+	}
+
+	fn get_command(&self) -> (String, Option<String>, CommandType) { // returns the value to copy
+		let value = self.values.get(self.selected.unwrap_or(0)).cloned();
+		(value.unwrap_or_default(), None, CommandType::Calculation)
+	}
+
+	// the search stays as typed, moving through the list picks which format gets copied
+	fn select_next(&mut self) -> (String, Option<String>) {
+		let index = match self.selected {
+			Some(index) if index + 1 < self.values.len() => index + 1,
+			_ => 0,
+		};
+
+		self.select(index)
+	}
+
+	fn select_previous(&mut self) -> (String, Option<String>) {
+		let index = match self.selected {
+			Some(index) if index > 0 => index - 1,
+			_ => self.values.len().saturating_sub(1),
+		};
+
+		self.select(index)
+	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		self.select(index)
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		Prompt {
+			mode: Some(String::from("calculator")),
+			placeholder: String::new(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CalculatorFactory;
+
+impl Factory for CalculatorFactory {
+	fn should_create(&self, search: &String) -> bool {
+		search.starts_with('=')
+	}
+
+	fn create(&self) -> Box<dyn State> {
+		Box::new(CalculatorState::default())
+	}
+}
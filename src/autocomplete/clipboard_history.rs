@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::autocomplete::program_sorting::autocomplete;
+use crate::autocomplete::types::{
+	ActiveList,
+	Autocomplete,
+	CommandType,
+	Factory,
+	List,
+	Prompt,
+	State,
+	get_ui_list,
+};
+use crate::clipboard_history::{ Entry, delete_entry, get_entries };
+use crate::git::relative_time;
+use crate::path_interpreter::ProgramFrequencyMap;
+
+pub struct ClipboardHistoryState {
+	active_list: ActiveList,
+	autocomplete: Option<Autocomplete>,
+	entries: Vec<(String, Entry)>, // newest first, labelled by their unique preview
+	factory: Box<dyn Factory>,
+	fuzzyfind: List,
+	labels: Vec<String>,
+	preamble: String,
+	search: String,
+	selected: Option<usize>,
+	subtitles: HashMap<String, String>,
+}
+
+// labels are what the ui selects by, so entries with the same preview get numbered
+fn label_entries(entries: Vec<Entry>) -> Vec<(String, Entry)> {
+	let mut labels = HashSet::new();
+	entries.into_iter()
+		.map(|entry| {
+			let mut label = entry.preview.clone();
+			let mut count = 1;
+			while labels.contains(&label) {
+				count += 1;
+				label = format!("{} ({})", entry.preview, count);
+			}
+			labels.insert(label.clone());
+
+			(label, entry)
+		})
+		.collect()
+}
+
+fn subtitle(entry: &Entry) -> Option<String> {
+	let time = relative_time(entry.timestamp?);
+	if entry.lines > 1 {
+		Some(format!("{} · {} lines", time, entry.lines))
+	} else {
+		Some(time)
+	}
+}
+
+impl Default for ClipboardHistoryState {
+	fn default() -> Self {
+		let mut state = ClipboardHistoryState {
+			active_list: ActiveList::default(),
+			autocomplete: None,
+			entries: Vec::new(),
+			factory: Box::new(ClipboardHistoryFactory),
+			fuzzyfind: List::default(),
+			labels: Vec::new(),
+			preamble: String::from("clip "),
+			search: String::default(),
+			selected: None,
+			subtitles: HashMap::new(),
+		};
+
+		state.set_entries(label_entries(get_entries()));
+		state
+	}
+}
+
+impl ClipboardHistoryState {
+	fn set_entries(&mut self, entries: Vec<(String, Entry)>) {
+		self.labels = entries.iter().map(|(label, _)| label.clone()).collect();
+		self.subtitles = entries.iter()
+			.filter_map(|(label, entry)| Some((label.clone(), subtitle(entry)?)))
+			.collect();
+		self.entries = entries;
+	}
+
+	// unlike programs the history keeps its order, the most recent copy is the most likely pick
+	fn update_lists(&mut self) {
+		let search = self.search.to_lowercase();
+		self.autocomplete = autocomplete(&self.labels, &ProgramFrequencyMap::default(), &self.search);
+		self.fuzzyfind = Some(
+			self.labels.iter()
+				.filter(|label| label.to_lowercase().contains(&search))
+				.cloned()
+				.collect()
+		);
+	}
+
+	fn find_entry(&self) -> Option<&(String, Entry)> {
+		let search = self.search.to_lowercase();
+		self.entries.iter()
+			.find(|(label, _)| label == &self.search)
+			.or_else(|| self.entries.iter().find(|(label, _)| label.to_lowercase().contains(&search)))
+	}
+}
+
+impl State for ClipboardHistoryState {
+	fn get_factory(&self) -> &Box<dyn Factory> {
+		&self.factory
+	}
+
+	fn get_replacement(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_preamble(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_active_list(&self) -> ActiveList {
+		self.active_list
+	}
+
+	fn get_autocomplete_list(&self) -> &List {
+		if let Some(autocomplete) = self.autocomplete.as_ref() {
+			&autocomplete.list
+		} else {
+			&None
+		}
+	}
+
+	fn get_fuzzyfinder_list(&self) -> &List {
+		&self.fuzzyfind
+	}
+
+	fn update_search(&mut self, search: String) {
+		self.search = search;
+		self.active_list = ActiveList::FuzzyFinder;
+		self.selected = None;
+
+		self.update_lists();
+	}
+
+	fn autocomplete(&mut self) -> (String, Option<String>) {
+		self.active_list = ActiveList::Autocomplete;
+		if let Some(list) = self.autocomplete.as_ref() && !list.common_start.is_empty() {
+			self.search = list.common_start.clone();
+		}
+
+		self.update_lists();
+
+		if !self.search.is_empty() {
+			self.selected = Some(0);
+		}
+
+		(self.search.clone(), None)
+	}
+
+	fn get_command(&self) -> (String, Option<String>, CommandType) { // returns the entry id
+		if let Some((_, entry)) = self.find_entry() {
+			(entry.id.clone(), None, CommandType::ClipboardEntry)
+		} else {
+			(String::new(), None, CommandType::ClipboardEntry)
+		}
+	}
+
+	fn select_next(&mut self) -> (String, Option<String>) {
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		if list.is_none_or(|list| list.is_empty()) {
+			self.selected = None;
+		} else if self.selected.is_some() && self.selected.unwrap() != list.unwrap().len() - 1 {
+			self.selected = Some(self.selected.unwrap() + 1);
+		} else {
+			self.selected = Some(0);
+		}
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn select_previous(&mut self) -> (String, Option<String>) {
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		if list.is_none_or(|list| list.is_empty()) {
+			self.selected = None;
+		} else if self.selected.is_some() && self.selected.unwrap() != 0 {
+			self.selected = Some(self.selected.unwrap() - 1);
+		} else {
+			self.selected = Some(list.unwrap().len() - 1);
+		}
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		self.selected = list.filter(|list| index < list.len()).map(|_| index);
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn get_subtitle(&self, item: &String) -> Option<&String> {
+		self.subtitles.get(item)
+	}
+
+	fn delete_selected(&mut self) -> Option<(String, Option<String>)> {
+		self.selected?;
+
+		let (label, entry) = self.find_entry()?.clone();
+		delete_entry(&entry.id);
+
+		let entries = std::mem::take(&mut self.entries).into_iter()
+			.filter(|(entry_label, _)| entry_label != &label)
+			.collect();
+		self.set_entries(entries);
+		self.update_search(String::new());
+
+		Some((String::new(), None))
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		Prompt {
+			mode: Some(String::from("clipboard")),
+			placeholder: String::new(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ClipboardHistoryFactory;
+
+impl Factory for ClipboardHistoryFactory {
+	fn should_create(&self, search: &String) -> bool {
+		search.starts_with("clip ")
+	}
+
+	fn create(&self) -> Box<dyn State> {
+		Box::new(ClipboardHistoryState::default())
+	}
+}
@@ -1,30 +1,57 @@
+use std::collections::{ HashMap, HashSet, };
+use std::path::Path;
+
+use crate::autocomplete::calculator::CalculatorFactory;
+use crate::autocomplete::clipboard_history::ClipboardHistoryFactory;
 use crate::autocomplete::killall::KillallFactory;
 use crate::autocomplete::open_project::OpenProjectFactory;
-use crate::autocomplete::program_sorting::{ autocomplete, fuzzyfind, };
+use crate::autocomplete::program_sorting::{ add_description_matches, autocomplete, fuzzyfind, };
+use crate::autocomplete::session::SessionFactory;
 use crate::autocomplete::sudo::SudoFactory;
+use crate::autocomplete::symbol_picker::SymbolPickerFactory;
+use crate::autocomplete::systemd::SystemdFactory;
 use crate::autocomplete::types::{
 	ActiveList,
 	Autocomplete,
 	CommandType,
+	CommitTimesLoader,
 	Factory,
 	List,
+	PROGRAM_ICON,
+	Prompt,
+	SHELL_ICON,
 	State,
+	SubtitleLoader,
+	WINDOW_ICON,
 	get_ui_list,
 	handle_update_placeholder,
 	passthrough_command,
 	passthrough_string,
 };
+use crate::autocomplete::window_switcher::{
+	WindowEntry,
+	WindowSwitcherFactory,
+	fuzzyfind_windows,
+};
 use crate::autocomplete::x11_forward::X11ForwardFactory;
+use crate::config::{ CONFIG, find_command, };
+use crate::desktop_entries::desktop_entries;
+use crate::error::reported;
 use crate::path_interpreter::{
 	ProgramFrequencyMap,
+	describe_program,
 	get_programs,
 	read_command_frequency,
 };
+use crate::shell::get_shell_entries;
+
+const DEFAULT_LIST_LENGTH: usize = 20;
 
 pub struct DefaultState {
 	active_list: ActiveList,
 	autocomplete: Option<Autocomplete>,
 	default_list: Option<Vec<String>>,
+	descriptions: HashMap<String, String>, // shown under an entry and searched after the names
 	factory: Box<dyn Factory>,
 	fuzzyfind: List,
 	passthrough: Option<Box<dyn State>>,
@@ -34,44 +61,132 @@ pub struct DefaultState {
 	program_frequency: ProgramFrequencyMap,
 	search: String,
 	selected: Option<usize>,
+	shell_entries: HashSet<String>, // aliases and functions, which have no icon or man page
+	subtitles: HashMap<String, Option<String>>, // contains an entry once a subtitle is requested
+	windows: Vec<WindowEntry>,
+}
+
+// open windows go above the programs so switching to something is preferred over launching it again
+fn merge_windows(windows: &[WindowEntry], search: &str, programs: List) -> List {
+	let mut output = fuzzyfind_windows(windows, search).iter()
+		.map(|entry| entry.switch_label())
+		.collect::<Vec<String>>();
+
+	output.extend(programs.unwrap_or_default());
+	Some(output)
+}
+
+// shell aliases and functions are launched like programs, a program with the same name wins
+fn add_shell_entries(programs: &mut Vec<String>) -> HashMap<String, String> {
+	let entries = get_shell_entries();
+	let mut subtitles = HashMap::new();
+	for (name, expansion) in entries.aliases {
+		subtitles.insert(name, format!("alias for {}", expansion));
+	}
+
+	for function in entries.functions {
+		subtitles.insert(function, String::from("shell function"));
+	}
+
+	subtitles.retain(|name, _| !programs.contains(name));
+	programs.extend(subtitles.keys().cloned());
+	subtitles
+}
+
+// custom commands are searched like programs and replace a program with the same name
+fn add_custom_commands(programs: &mut Vec<String>) {
+	for command in CONFIG.commands.iter() {
+		if !programs.contains(&command.label) {
+			programs.push(command.label.clone());
+		}
+	}
+}
+
+// descriptions that are known up front, the rest are looked up for the rows that are on screen
+fn get_descriptions(programs: &[String], shell_subtitles: HashMap<String, String>) -> HashMap<String, String> {
+	let mut descriptions = shell_subtitles;
+	for command in CONFIG.commands.iter() {
+		if let Some(description) = command.description.as_ref() {
+			descriptions.insert(command.label.clone(), description.clone());
+		}
+	}
+
+	let programs = programs.iter().collect::<HashSet<&String>>();
+	for entry in desktop_entries() {
+		if let (Some(program), Some(comment)) = (entry.program.as_ref(), entry.comment.as_ref())
+			&& programs.contains(program)
+		{
+			descriptions.entry(program.clone()).or_insert(comment.clone());
+		}
+	}
+
+	descriptions
+}
+
+// shown before anything is typed, the custom commands and whatever was launched before
+fn get_default_list(programs: &[String], program_frequency: &ProgramFrequencyMap) -> List {
+	let candidates = programs.iter()
+		.filter(|program| {
+			find_command(program).is_some() || program_frequency.map.contains_key(*program)
+		})
+		.cloned()
+		.collect::<Vec<String>>();
+
+	fuzzyfind(&candidates, program_frequency, &String::new())
+		.map(|list| list.into_iter().take(DEFAULT_LIST_LENGTH).collect())
 }
 
 impl Default for DefaultState {
 	fn default() -> Self {
+		let mut programs = get_programs().unwrap();
+		let shell_subtitles = add_shell_entries(&mut programs);
+		add_custom_commands(&mut programs);
+		let program_frequency = read_command_frequency().unwrap_or_else(reported);
+		let shell_entries = shell_subtitles.keys().cloned().collect();
+		let descriptions = get_descriptions(&programs, shell_subtitles);
+
 		DefaultState {
 			active_list: ActiveList::default(),
 			autocomplete: None,
-			default_list: Some(
-				vec![
-					"gitkraken",
-					"okular",
-					"steam",
-					"warsow",
-					"texstudio",
-					"restart",
-					"off",
-				].iter()
-				.map(|s| s.to_string())
-				.collect::<Vec<String>>()
-			),
+			default_list: get_default_list(&programs, &program_frequency),
+			descriptions,
 			factory: Box::new(DefaultFactory),
 			fuzzyfind: List::default(),
 			passthrough: None,
 			passthrough_factories: vec![
+				Box::new(CalculatorFactory),
+				Box::new(ClipboardHistoryFactory),
 				Box::new(OpenProjectFactory),
+				Box::new(SessionFactory),
 				Box::new(SudoFactory),
 				Box::new(KillallFactory),
+				Box::new(SymbolPickerFactory),
+				Box::new(SystemdFactory),
+				Box::new(WindowSwitcherFactory),
 				Box::new(X11ForwardFactory)
 			],
 			preamble: String::new(),
-			programs: get_programs().unwrap(),
-			program_frequency: read_command_frequency(),
+			programs,
+			program_frequency,
 			search: String::default(),
 			selected: None,
+			shell_entries,
+			subtitles: HashMap::new(),
+			windows: Vec::new(),
 		}
 	}
 }
 
+impl DefaultState {
+	fn update_lists(&mut self) {
+		let mut programs = fuzzyfind(&self.programs, &self.program_frequency, &self.search);
+		add_description_matches(&mut programs, &self.descriptions, &self.program_frequency, &self.search);
+
+		self.autocomplete = autocomplete(&self.programs, &self.program_frequency, &self.search);
+		self.fuzzyfind = merge_windows(&self.windows, &self.search, programs);
+	}
+}
+
 impl State for DefaultState {
 	fn get_factory(&self) -> &Box<dyn Factory> {
 		&self.factory
@@ -133,8 +248,7 @@ impl State for DefaultState {
 			self.active_list = ActiveList::FuzzyFinder;
 			self.selected = None;
 
-			self.autocomplete = autocomplete(&self.programs, &self.program_frequency, &self.search);
-			self.fuzzyfind = fuzzyfind(&self.programs, &self.program_frequency, &self.search);
+			self.update_lists();
 		}
 	}
 
@@ -150,8 +264,7 @@ impl State for DefaultState {
 			}
 		}
 
-		self.autocomplete = autocomplete(&self.programs, &self.program_frequency, &self.search);
-		self.fuzzyfind = fuzzyfind(&self.programs, &self.program_frequency, &self.search);
+		self.update_lists();
 
 		if self.search.clone().len() != 0 {
 			self.selected = Some(0);
@@ -161,9 +274,22 @@ impl State for DefaultState {
 	}
 
 	fn get_command(&self) -> (String, Option<String>, CommandType) {
-		if self.search.len() == 0 && self.selected.is_some() {
+		let window = self.windows.iter().find(|entry| entry.switch_label() == self.search);
+		let command = if self.search.is_empty() {
+			self.selected.and_then(|index| self.default_list.as_ref()?.get(index))
+		} else {
+			Some(&self.search)
+		};
+
+		if let (None, Some(command)) = (self.passthrough.as_ref(), command)
+			&& find_command(command).is_some()
+		{
+			(command.clone(), None, CommandType::Custom)
+		} else if self.search.len() == 0 && self.selected.is_some() {
 			let command = self.default_list.as_ref().unwrap()[self.selected.unwrap()].clone();
 			(command.clone(), Some(command), CommandType::Normal)
+		} else if let (None, Some(entry)) = (self.passthrough.as_ref(), window) {
+			(entry.window.id.clone(), None, CommandType::FocusWindow)
 		} else {
 			passthrough_command(
 				&self.search,
@@ -174,9 +300,9 @@ impl State for DefaultState {
 		}
 	}
 
-	fn select_up(&mut self) -> (String, Option<String>) {
+	fn select_next(&mut self) -> (String, Option<String>) {
 		if let Some(passthrough) = self.passthrough.as_mut() {
-			return passthrough_string(&self.search, passthrough.select_up());
+			return passthrough_string(&self.search, passthrough.select_next());
 		}
 
 		let list = if self.search.len() == 0 {
@@ -185,7 +311,7 @@ impl State for DefaultState {
 			get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref()
 		};
 
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != list.unwrap().len() - 1 {
 			self.selected = Some(self.selected.unwrap() + 1);
@@ -204,9 +330,9 @@ impl State for DefaultState {
 		}
 	}
 
-	fn select_down(&mut self) -> (String, Option<String>) {
+	fn select_previous(&mut self) -> (String, Option<String>) {
 		if let Some(passthrough) = self.passthrough.as_mut() {
-			return passthrough_string(&self.search, passthrough.select_down());
+			return passthrough_string(&self.search, passthrough.select_previous());
 		}
 
 		let list = if self.search.len() == 0 {
@@ -215,7 +341,7 @@ impl State for DefaultState {
 			get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref()
 		};
 
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != 0 {
 			self.selected = Some(self.selected.unwrap() - 1);
@@ -233,6 +359,150 @@ impl State for DefaultState {
 			(String::new(), None)
 		}
 	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			return passthrough_string(&self.search, passthrough.select_index(index));
+		}
+
+		let list = if self.search.is_empty() {
+			self.default_list.as_ref()
+		} else {
+			get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref()
+		};
+		self.selected = list.filter(|list| index < list.len()).map(|_| index);
+
+		if let Some(index) = self.selected {
+			if !self.search.is_empty() {
+				self.search = list.unwrap()[index].clone();
+			}
+
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn show_actions(&mut self) -> Option<(String, Option<String>)> {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			passthrough.show_actions().map(|actions| passthrough_string(&self.search, actions))
+		} else {
+			None
+		}
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_prompt()
+		} else {
+			Prompt {
+				mode: None,
+				placeholder: String::from("run a program, or start with sudo, = or !"),
+			}
+		}
+	}
+
+	fn get_icon(&self, item: &String) -> Option<&str> {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_icon(item)
+		} else if let Some(command) = find_command(item) {
+			command.icon.as_deref()
+		} else if self.windows.iter().any(|entry| &entry.switch_label() == item) {
+			Some(WINDOW_ICON)
+		} else if self.shell_entries.contains(item) {
+			Some(SHELL_ICON)
+		} else {
+			Some(PROGRAM_ICON)
+		}
+	}
+
+	fn get_icon_name(&self, item: &String) -> Option<String> {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_icon_name(item)
+		} else if let Some(entry) = self.windows.iter().find(|entry| &entry.switch_label() == item) {
+			Some(entry.window.app_id.clone())
+		} else if find_command(item).is_some() || self.shell_entries.contains(item) {
+			None
+		} else {
+			Some(item.clone())
+		}
+	}
+
+	fn get_subtitle(&self, item: &String) -> Option<&String> {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_subtitle(item)
+		} else {
+			self.windows.iter()
+				.find(|entry| &entry.switch_label() == item)
+				.map(|entry| &entry.subtitle)
+				.or_else(|| self.descriptions.get(item))
+				.or_else(|| self.subtitles.get(item).and_then(|subtitle| subtitle.as_ref()))
+		}
+	}
+
+	fn subtitle_requests(&mut self, items: &[String]) -> Vec<(String, SubtitleLoader)> {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			return passthrough.subtitle_requests(items);
+		}
+
+		let mut requests: Vec<(String, SubtitleLoader)> = Vec::new();
+		for item in items.iter() {
+			if self.subtitles.contains_key(item)
+				|| self.descriptions.contains_key(item)
+				|| find_command(item).is_some()
+				|| self.windows.iter().any(|entry| &entry.switch_label() == item)
+			{
+				continue;
+			}
+
+			let program = item.clone();
+			self.subtitles.insert(item.clone(), None);
+			requests.push((item.clone(), Box::new(move || describe_program(&program))));
+		}
+
+		requests
+	}
+
+	fn set_subtitle(&mut self, item: String, subtitle: Option<String>) {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			passthrough.set_subtitle(item, subtitle);
+			return;
+		}
+
+		// man page summaries are worth searching once we have them, paths are not
+		if let Some(subtitle) = subtitle.as_ref()
+			&& !Path::new(subtitle).is_absolute()
+		{
+			self.descriptions.insert(item.clone(), subtitle.clone());
+		}
+
+		self.subtitles.insert(item, subtitle);
+	}
+
+	fn set_windows(&mut self, windows: Vec<WindowEntry>) {
+		self.windows = windows;
+		if !self.search.is_empty() {
+			self.update_lists();
+		}
+	}
+
+	fn commit_times_request(&mut self) -> Option<CommitTimesLoader> {
+		self.passthrough.as_mut()?.commit_times_request()
+	}
+
+	fn set_commit_times(&mut self, commit_times: HashMap<String, u64>) {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			passthrough.set_commit_times(commit_times);
+		}
+	}
+
+	fn delete_selected(&mut self) -> Option<(String, Option<String>)> {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			passthrough.delete_selected().map(|search| passthrough_string(&self.search, search))
+		} else {
+			None
+		}
+	}
 }
 
 #[derive(Clone, Debug, Default)]
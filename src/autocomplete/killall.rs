@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::autocomplete::open_project::OpenProjectFactory;
 use crate::autocomplete::program_sorting::{ autocomplete, fuzzyfind, };
 use crate::autocomplete::types::{
@@ -6,12 +8,17 @@ use crate::autocomplete::types::{
 	CommandType,
 	Factory,
 	List,
+	PROCESS_ICON,
+	Prompt,
 	State,
+	SubtitleLoader,
 	get_ui_list,
 	handle_update_placeholder,
 	passthrough_command,
+	passthrough_prompt,
 	passthrough_string,
 };
+use crate::error::reported;
 use crate::path_interpreter::{
 	ProgramFrequencyMap,
 	get_programs,
@@ -30,6 +37,27 @@ pub struct KillallState {
 	program_frequency: ProgramFrequencyMap,
 	search: String,
 	selected: Option<usize>,
+	subtitles: HashMap<String, Option<String>>, // contains an entry once a subtitle is requested
+}
+
+// which processes would be killed, so it is clear whether anything is running under that name
+fn process_summary(program: &str) -> Option<String> {
+	let output = std::process::Command::new("pgrep")
+		.arg("-x")
+		.arg(program)
+		.output()
+		.ok()?;
+
+	let pids = String::from_utf8_lossy(&output.stdout)
+		.lines()
+		.map(str::to_string)
+		.collect::<Vec<String>>();
+
+	match pids.len() {
+		0 => Some(String::from("not running")),
+		1 => Some(format!("running · pid {}", pids[0])),
+		count => Some(format!("{} processes · pids {}", count, pids.join(", "))),
+	}
 }
 
 impl Default for KillallState {
@@ -43,9 +71,10 @@ impl Default for KillallState {
 			passthrough_factories: vec![Box::new(OpenProjectFactory)],
 			preamble: String::from("killall "),
 			programs: get_programs().unwrap(),
-			program_frequency: read_command_frequency(),
+			program_frequency: read_command_frequency().unwrap_or_else(reported),
 			search: String::default(),
 			selected: None,
+			subtitles: HashMap::new(),
 		}
 	}
 }
@@ -134,13 +163,13 @@ impl State for KillallState {
 		passthrough_command(&self.search, &String::from("killall"), CommandType::Normal, &self.passthrough)
 	}
 
-	fn select_up(&mut self) -> (String, Option<String>) {
+	fn select_next(&mut self) -> (String, Option<String>) {
 		if let Some(passthrough) = self.passthrough.as_mut() {
-			return passthrough_string(&self.search, passthrough.select_up());
+			return passthrough_string(&self.search, passthrough.select_next());
 		}
 
 		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != list.unwrap().len() - 1 {
 			self.selected = Some(self.selected.unwrap() + 1);
@@ -156,13 +185,13 @@ impl State for KillallState {
 		}
 	}
 
-	fn select_down(&mut self) -> (String, Option<String>) {
+	fn select_previous(&mut self) -> (String, Option<String>) {
 		if let Some(passthrough) = self.passthrough.as_mut() {
-			return passthrough_string(&self.search, passthrough.select_down());
+			return passthrough_string(&self.search, passthrough.select_previous());
 		}
 
 		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != 0 {
 			self.selected = Some(self.selected.unwrap() - 1);
@@ -177,6 +206,85 @@ impl State for KillallState {
 			(String::new(), None)
 		}
 	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			return passthrough_string(&self.search, passthrough.select_index(index));
+		}
+
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		self.selected = list.filter(|list| index < list.len()).map(|_| index);
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn show_actions(&mut self) -> Option<(String, Option<String>)> {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			passthrough.show_actions().map(|actions| passthrough_string(&self.search, actions))
+		} else {
+			None
+		}
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		passthrough_prompt("killall", &self.passthrough)
+	}
+
+	fn get_icon(&self, item: &String) -> Option<&str> {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_icon(item)
+		} else {
+			Some(PROCESS_ICON)
+		}
+	}
+
+	fn get_icon_name(&self, item: &String) -> Option<String> {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_icon_name(item)
+		} else {
+			Some(item.clone())
+		}
+	}
+
+	fn get_subtitle(&self, item: &String) -> Option<&String> {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_subtitle(item)
+		} else {
+			self.subtitles.get(item).and_then(|subtitle| subtitle.as_ref())
+		}
+	}
+
+	fn subtitle_requests(&mut self, items: &[String]) -> Vec<(String, SubtitleLoader)> {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			return passthrough.subtitle_requests(items);
+		}
+
+		let mut requests: Vec<(String, SubtitleLoader)> = Vec::new();
+		for item in items.iter() {
+			if self.subtitles.contains_key(item) {
+				continue;
+			}
+
+			let program = item.clone();
+			self.subtitles.insert(item.clone(), None);
+			requests.push((item.clone(), Box::new(move || process_summary(&program))));
+		}
+
+		requests
+	}
+
+	fn set_subtitle(&mut self, item: String, subtitle: Option<String>) {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			passthrough.set_subtitle(item, subtitle);
+		} else {
+			self.subtitles.insert(item, subtitle);
+		}
+	}
 }
 
 #[derive(Clone, Debug, Default)]
@@ -1,11 +1,21 @@
+pub mod calculator;
+pub mod clipboard_history;
 pub mod default;
 pub mod killall;
 pub mod open_project;
 pub mod program_sorting;
+pub mod session;
 pub mod sudo;
+pub mod symbol_picker;
+pub mod systemd;
 mod types;
+pub mod window_switcher;
 pub mod x11_forward;
 
 pub use types::CommandType;
 pub use types::State;
 pub use types::Factory;
+pub use types::ProjectAction;
+pub use types::SessionAction;
+pub use types::UnitAction;
+pub use types::UnitScope;
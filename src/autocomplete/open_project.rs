@@ -1,103 +1,102 @@
+use std::collections::HashMap;
+
+use crate::autocomplete::program_sorting::{ autocomplete, fuzzyfind, };
 use crate::autocomplete::types::{
 	ActiveList,
 	Autocomplete,
 	CommandType,
+	CommitTimesLoader,
 	Factory,
 	List,
+	PROJECT_ICON,
+	ProjectAction,
+	Prompt,
 	State,
+	SubtitleLoader,
 	get_ui_list,
 };
-use crate::path_interpreter::get_projects;
-
-fn fuzzyfind(projects: &Vec<String>, search: &String) -> List {
-	let mut output = projects.iter()
-		.fold(Vec::new(), |mut acc, program| {
-			if program.find(search).is_some() {
-				acc.push(program.clone())
-			}
-
-			acc
-		});
-
-	output.sort_by(
-		|a, b| {
-			a.len().cmp(&b.len())
-		}
-	);
-
-	Some(output)
-}
-
-fn autocomplete(projects: &Vec<String>, search: &String) -> Option<Autocomplete> {
-	let mut common_start: Option<String> = None;
-	let mut output = projects.iter()
-		.fold(Vec::new(), |mut acc, project| {
-			if let Some(index) = project.find(search) {
-				if index == 0 {
-					acc.push(project.clone());
-
-					if let Some(common) = common_start.as_ref() {
-						let mut stop = None;
-						let mut project_chars = project.chars();
-						let mut common_start_chars = common.chars();
-						for i in 0..std::cmp::min(project.len(), common.len()) {
-							if project_chars.next() != common_start_chars.next() {
-								stop = Some(i);
-								break;
-							}
-						}
-
-						if stop == None && common.len() > project.len() {
-							stop = Some(project.len());
-						}
-
-						if let Some(index) = stop {
-							common_start = Some(common[0..index].to_string());
-						}
-					} else {
-						common_start = Some(project.clone());
-					}
-				}
-			}
-
-			acc
-		});
-
-	output.sort_by(
-		|a, b| {
-			a.len().cmp(&b.len())
-		}
-	);
+use crate::config::CONFIG;
+use crate::error::reported;
+use crate::git::{ last_commit_time, summary, };
+use crate::path_interpreter::{
+	Project,
+	ProgramFrequencyMap,
+	get_projects,
+	read_project_frequency,
+};
 
-	Some(Autocomplete {
-		common_start: common_start.unwrap_or_default(),
-		list: Some(output),
-	})
+// projects that have never been opened keep their length ordering unless we know when they were
+// last committed to, in which case the most recently worked on ones come first
+fn sort_by_commit_time(
+	list: &mut List,
+	project_frequency: &ProgramFrequencyMap,
+	commit_times: &HashMap<String, u64>,
+) {
+	if let Some(list) = list.as_mut() {
+		let start = list.iter()
+			.position(|project| !project_frequency.map.contains_key(project))
+			.unwrap_or(list.len());
+
+		list[start..].sort_by(|a, b| commit_times.get(b).cmp(&commit_times.get(a)));
+	}
 }
 
 pub struct OpenProjectState {
+	action_list: List,
 	active_list: ActiveList,
 	autocomplete: Option<Autocomplete>,
+	commit_times: HashMap<String, u64>,
+	commit_times_requested: bool,
 	factory: Box<dyn Factory>,
 	fuzzyfind: List,
 	preamble: String,
-	projects: Vec<String>,
+	project_frequency: ProgramFrequencyMap,
+	project_names: Vec<String>,
+	projects: Vec<Project>,
 	search: String,
 	selected: Option<usize>,
+	showing_actions: bool, // list the actions for the selected project instead of projects
+	subtitles: HashMap<String, Option<String>>, // contains an entry once a subtitle is requested
 }
 
 impl Default for OpenProjectState {
 	fn default() -> Self {
+		let projects = get_projects();
+
 		OpenProjectState {
+			action_list: Some(
+				ProjectAction::ALL.iter()
+					.map(|action| action.label().to_string())
+					.collect::<Vec<String>>()
+			),
 			active_list: ActiveList::default(),
 			autocomplete: None,
+			commit_times: HashMap::new(),
+			commit_times_requested: !CONFIG.projects.boost_by_commit_time,
 			factory: Box::new(OpenProjectFactory),
 			fuzzyfind: List::default(),
 			preamble: String::from("open-project "),
-			projects: get_projects().unwrap(),
+			project_frequency: read_project_frequency().unwrap_or_else(reported),
+			project_names: projects.iter().map(|project| project.name.clone()).collect(),
+			projects,
 			search: String::default(),
 			selected: None,
+			showing_actions: false,
+			subtitles: HashMap::new(),
+		}
+	}
+}
+
+impl OpenProjectState {
+	fn update_lists(&mut self) {
+		self.autocomplete = autocomplete(&self.project_names, &self.project_frequency, &self.search);
+		self.fuzzyfind = fuzzyfind(&self.project_names, &self.project_frequency, &self.search);
+
+		if let Some(autocomplete) = self.autocomplete.as_mut() {
+			sort_by_commit_time(&mut autocomplete.list, &self.project_frequency, &self.commit_times);
 		}
+
+		sort_by_commit_time(&mut self.fuzzyfind, &self.project_frequency, &self.commit_times);
 	}
 }
 
@@ -115,7 +114,11 @@ impl State for OpenProjectState {
 	}
 
 	fn get_active_list(&self) -> ActiveList {
-		self.active_list
+		if self.showing_actions {
+			ActiveList::FuzzyFinder
+		} else {
+			self.active_list
+		}
 	}
 
 	fn get_autocomplete_list(&self) -> &List {
@@ -127,19 +130,24 @@ impl State for OpenProjectState {
 	}
 
 	fn get_fuzzyfinder_list(&self) -> &List {
-		&self.fuzzyfind
+		if self.showing_actions {
+			&self.action_list
+		} else {
+			&self.fuzzyfind
+		}
 	}
 
 	fn update_search(&mut self, search: String) {
+		self.showing_actions = false;
 		self.search = search;
 		self.active_list = ActiveList::FuzzyFinder;
 		self.selected = None;
 
-		self.autocomplete = autocomplete(&self.projects, &self.search);
-		self.fuzzyfind = fuzzyfind(&self.projects, &self.search);
+		self.update_lists();
 	}
 
 	fn autocomplete(&mut self) -> (String, Option<String>) {
+		self.showing_actions = false;
 		self.active_list = ActiveList::Autocomplete;
 		if let Some(list) = self.autocomplete.as_ref() {
 			if list.common_start.len() != 0 {
@@ -147,8 +155,7 @@ impl State for OpenProjectState {
 			}
 		}
 
-		self.autocomplete = autocomplete(&self.projects, &self.search);
-		self.fuzzyfind = fuzzyfind(&self.projects, &self.search);
+		self.update_lists();
 
 		if self.search.clone().len() != 0 {
 			self.selected = Some(0);
@@ -157,13 +164,33 @@ impl State for OpenProjectState {
 		(self.search.clone(), None)
 	}
 
-	fn get_command(&self) -> (String, Option<String>, CommandType) { // only returns the project folder name
-		(self.search.clone(), None, CommandType::OpenProject)
+	fn get_command(&self) -> (String, Option<String>, CommandType) { // only returns the project path
+		let action = if self.showing_actions {
+			ProjectAction::ALL[self.selected.unwrap_or(0)]
+		} else {
+			CONFIG.projects.actions.default
+		};
+
+		if let Some(project) = self.projects.iter().find(|project| project.name == self.search) {
+			(
+				project.path.to_string_lossy().to_string(),
+				Some(project.name.clone()),
+				CommandType::OpenProject(action),
+			)
+		} else {
+			(self.search.clone(), None, CommandType::OpenProject(action))
+		}
 	}
 
-	fn select_up(&mut self) -> (String, Option<String>) {
+	fn select_next(&mut self) -> (String, Option<String>) {
+		if self.showing_actions {
+			let index = (self.selected.unwrap_or(0) + 1) % ProjectAction::ALL.len();
+			self.selected = Some(index);
+			return (self.search.clone(), Some(ProjectAction::ALL[index].label().to_string()));
+		}
+
 		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != list.unwrap().len() - 1 {
 			self.selected = Some(self.selected.unwrap() + 1);
@@ -179,9 +206,15 @@ impl State for OpenProjectState {
 		}
 	}
 
-	fn select_down(&mut self) -> (String, Option<String>) {
+	fn select_previous(&mut self) -> (String, Option<String>) {
+		if self.showing_actions {
+			let index = (self.selected.unwrap_or(0) + ProjectAction::ALL.len() - 1) % ProjectAction::ALL.len();
+			self.selected = Some(index);
+			return (self.search.clone(), Some(ProjectAction::ALL[index].label().to_string()));
+		}
+
 		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != 0 {
 			self.selected = Some(self.selected.unwrap() - 1);
@@ -196,6 +229,106 @@ impl State for OpenProjectState {
 			(String::new(), None)
 		}
 	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		if self.showing_actions {
+			let index = index.min(ProjectAction::ALL.len() - 1);
+			self.selected = Some(index);
+			return (self.search.clone(), Some(ProjectAction::ALL[index].label().to_string()));
+		}
+
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		self.selected = list.filter(|list| index < list.len()).map(|_| index);
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn show_actions(&mut self) -> Option<(String, Option<String>)> {
+		if !self.projects.iter().any(|project| project.name == self.search) {
+			return None;
+		}
+
+		self.showing_actions = true;
+		self.selected = Some(0);
+		Some((self.search.clone(), Some(ProjectAction::ALL[0].label().to_string())))
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		Prompt {
+			mode: Some(String::from("project")),
+			placeholder: String::new(),
+		}
+	}
+
+	fn get_icon(&self, _item: &String) -> Option<&str> {
+		if self.showing_actions {
+			None
+		} else {
+			Some(PROJECT_ICON)
+		}
+	}
+
+	fn get_subtitle(&self, item: &String) -> Option<&String> {
+		if self.showing_actions {
+			return None;
+		}
+
+		self.subtitles.get(item).and_then(|subtitle| subtitle.as_ref())
+	}
+
+	fn subtitle_requests(&mut self, items: &[String]) -> Vec<(String, SubtitleLoader)> {
+		if self.showing_actions {
+			return Vec::new();
+		}
+
+		let mut requests: Vec<(String, SubtitleLoader)> = Vec::new();
+		for item in items.iter() {
+			if self.subtitles.contains_key(item) {
+				continue;
+			}
+
+			if let Some(project) = self.projects.iter().find(|project| &project.name == item) {
+				let path = project.path.clone();
+				self.subtitles.insert(item.clone(), None);
+				requests.push((item.clone(), Box::new(move || summary(&path))));
+			}
+		}
+
+		requests
+	}
+
+	fn set_subtitle(&mut self, item: String, subtitle: Option<String>) {
+		self.subtitles.insert(item, subtitle);
+	}
+
+	fn commit_times_request(&mut self) -> Option<CommitTimesLoader> {
+		if self.commit_times_requested {
+			return None;
+		}
+
+		self.commit_times_requested = true;
+		let projects = self.projects.iter()
+			.map(|project| (project.name.clone(), project.path.clone()))
+			.collect::<Vec<_>>();
+
+		Some(Box::new(move || {
+			projects.into_iter()
+				.filter_map(|(name, path)| Some((name, last_commit_time(&path)?)))
+				.collect()
+		}))
+	}
+
+	fn set_commit_times(&mut self, commit_times: HashMap<String, u64>) {
+		self.commit_times = commit_times;
+		if !self.showing_actions {
+			self.update_lists();
+		}
+	}
 }
 
 #[derive(Clone, Debug, Default)]
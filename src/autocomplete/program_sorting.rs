@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
 use crate::autocomplete::types::Autocomplete;
 use crate::path_interpreter::{compare_program_frequency, ProgramFrequencyMap};
@@ -37,6 +38,33 @@ pub fn fuzzyfind(
 	Some(output)
 }
 
+// programs whose description matches go below the ones whose name does
+pub fn add_description_matches(
+	list: &mut Option<Vec<String>>,
+	descriptions: &HashMap<String, String>,
+	program_frequency: &ProgramFrequencyMap,
+	search: &str,
+) {
+	if search.is_empty() {
+		return;
+	}
+
+	let search = search.to_lowercase();
+	if let Some(list) = list.as_mut() {
+		let listed = list.iter().collect::<HashSet<&String>>();
+		let mut matches = descriptions
+			.iter()
+			.filter(|(program, description)| {
+				!listed.contains(program) && description.to_lowercase().contains(&search)
+			})
+			.map(|(program, _)| program.clone())
+			.collect::<Vec<String>>();
+
+		matches.sort_by(|a, b| sort_program(a, b, program_frequency));
+		list.extend(matches);
+	}
+}
+
 pub fn autocomplete(
 	programs: &Vec<String>,
 	program_frequency: &ProgramFrequencyMap,
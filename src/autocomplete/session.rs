@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use crate::autocomplete::types::{
+	ActiveList,
+	CommandType,
+	Factory,
+	List,
+	Prompt,
+	SessionAction,
+	State,
+};
+use crate::config::CONFIG;
+
+// session actions never go through the frecency store, so they keep this order
+pub struct SessionState {
+	commands: HashMap<String, String>, // label to the command that runs, shown under each action
+	factory: Box<dyn Factory>,
+	fuzzyfind: List,
+	preamble: String,
+	search: String,
+	selected: Option<usize>,
+}
+
+impl Default for SessionState {
+	fn default() -> Self {
+		let mut state = SessionState {
+			commands: SessionAction::ALL.iter()
+				.map(|action| (action.label().to_string(), CONFIG.session.command(*action)))
+				.collect(),
+			factory: Box::new(SessionFactory),
+			fuzzyfind: List::default(),
+			preamble: String::from("session "),
+			search: String::default(),
+			selected: None,
+		};
+
+		state.update_lists();
+		state
+	}
+}
+
+impl SessionState {
+	fn update_lists(&mut self) {
+		let search = self.search.to_lowercase();
+		self.fuzzyfind = Some(
+			SessionAction::ALL.iter()
+				.map(|action| action.label().to_string())
+				.filter(|label| label.contains(&search))
+				.collect()
+		);
+	}
+
+	fn select(&mut self, index: Option<usize>) -> (String, Option<String>) {
+		self.selected = index;
+		if let Some(index) = index {
+			self.search = self.fuzzyfind.as_ref().unwrap()[index].clone();
+			(self.search.clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+}
+
+impl State for SessionState {
+	fn get_factory(&self) -> &Box<dyn Factory> {
+		&self.factory
+	}
+
+	fn get_replacement(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_preamble(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_active_list(&self) -> ActiveList {
+		ActiveList::FuzzyFinder
+	}
+
+	fn get_autocomplete_list(&self) -> &List {
+		&None
+	}
+
+	fn get_fuzzyfinder_list(&self) -> &List {
+		&self.fuzzyfind
+	}
+
+	fn update_search(&mut self, search: String) {
+		self.search = search;
+		self.selected = None;
+
+		self.update_lists();
+	}
+
+	fn autocomplete(&mut self) -> (String, Option<String>) {
+		let length = self.fuzzyfind.as_ref().map_or(0, |list| list.len());
+		self.select(if length == 0 { None } else { Some(0) })
+	}
+
+	fn get_command(&self) -> (String, Option<String>, CommandType) { // returns the action label
+		let search = self.search.to_lowercase();
+		let action = SessionAction::ALL.iter()
+			.find(|action| action.label() == search)
+			.or_else(|| SessionAction::ALL.iter().find(|action| action.label().contains(&search)));
+
+		if let Some(action) = action {
+			(action.label().to_string(), None, CommandType::Session(*action))
+		} else {
+			(String::new(), None, CommandType::Normal)
+		}
+	}
+
+	fn select_next(&mut self) -> (String, Option<String>) {
+		let length = self.fuzzyfind.as_ref().map_or(0, |list| list.len());
+		let index = match self.selected {
+			_ if length == 0 => None,
+			Some(index) if index + 1 < length => Some(index + 1),
+			_ => Some(0),
+		};
+
+		self.select(index)
+	}
+
+	fn select_previous(&mut self) -> (String, Option<String>) {
+		let length = self.fuzzyfind.as_ref().map_or(0, |list| list.len());
+		let index = match self.selected {
+			_ if length == 0 => None,
+			Some(index) if index > 0 => Some(index - 1),
+			_ => Some(length - 1),
+		};
+
+		self.select(index)
+	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		let length = self.fuzzyfind.as_ref().map_or(0, |list| list.len());
+		self.select(Some(index).filter(|index| *index < length))
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		Prompt {
+			mode: Some(String::from("session")),
+			placeholder: String::new(),
+		}
+	}
+
+	fn get_icon(&self, item: &String) -> Option<&str> {
+		SessionAction::ALL.iter()
+			.find(|action| action.label() == item)
+			.map(|action| action.icon())
+	}
+
+	fn get_subtitle(&self, item: &String) -> Option<&String> {
+		self.commands.get(item)
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SessionFactory;
+
+impl Factory for SessionFactory {
+	fn should_create(&self, search: &String) -> bool {
+		search.starts_with("session ")
+	}
+
+	fn create(&self) -> Box<dyn State> {
+		Box::new(SessionState::default())
+	}
+}
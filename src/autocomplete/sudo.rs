@@ -7,12 +7,17 @@ use crate::autocomplete::types::{
 	CommandType,
 	Factory,
 	List,
+	PROGRAM_ICON,
+	Prompt,
 	State,
+	SubtitleLoader,
 	get_ui_list,
 	handle_update_placeholder,
 	passthrough_command,
+	passthrough_prompt,
 	passthrough_string,
 };
+use crate::error::reported;
 use crate::path_interpreter::{
 	ProgramFrequencyMap,
 	get_programs,
@@ -44,7 +49,7 @@ impl Default for SudoState {
 			passthrough_factories: vec![Box::new(OpenProjectFactory), Box::new(KillallFactory)],
 			preamble: String::from("sudo "),
 			programs: get_programs().unwrap(),
-			program_frequency: read_command_frequency(),
+			program_frequency: read_command_frequency().unwrap_or_else(reported),
 			search: String::default(),
 			selected: None,
 		}
@@ -140,13 +145,13 @@ impl State for SudoState {
 		)
 	}
 
-	fn select_up(&mut self) -> (String, Option<String>) {
+	fn select_next(&mut self) -> (String, Option<String>) {
 		if let Some(passthrough) = self.passthrough.as_mut() {
-			return passthrough_string(&self.search, passthrough.select_up());
+			return passthrough_string(&self.search, passthrough.select_next());
 		}
 
 		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != list.unwrap().len() - 1 {
 			self.selected = Some(self.selected.unwrap() + 1);
@@ -162,13 +167,13 @@ impl State for SudoState {
 		}
 	}
 
-	fn select_down(&mut self) -> (String, Option<String>) {
+	fn select_previous(&mut self) -> (String, Option<String>) {
 		if let Some(passthrough) = self.passthrough.as_mut() {
-			return passthrough_string(&self.search, passthrough.select_down());
+			return passthrough_string(&self.search, passthrough.select_previous());
 		}
 
 		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != 0 {
 			self.selected = Some(self.selected.unwrap() - 1);
@@ -183,6 +188,72 @@ impl State for SudoState {
 			(String::new(), None)
 		}
 	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			return passthrough_string(&self.search, passthrough.select_index(index));
+		}
+
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		self.selected = list.filter(|list| index < list.len()).map(|_| index);
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn show_actions(&mut self) -> Option<(String, Option<String>)> {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			passthrough.show_actions().map(|actions| passthrough_string(&self.search, actions))
+		} else {
+			None
+		}
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		passthrough_prompt("sudo", &self.passthrough)
+	}
+
+	fn get_icon(&self, item: &String) -> Option<&str> {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_icon(item)
+		} else {
+			Some(PROGRAM_ICON)
+		}
+	}
+
+	fn get_icon_name(&self, item: &String) -> Option<String> {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_icon_name(item)
+		} else {
+			Some(item.clone())
+		}
+	}
+
+	fn get_subtitle(&self, item: &String) -> Option<&String> {
+		if let Some(passthrough) = self.passthrough.as_ref() {
+			passthrough.get_subtitle(item)
+		} else {
+			None
+		}
+	}
+
+	fn subtitle_requests(&mut self, items: &[String]) -> Vec<(String, SubtitleLoader)> {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			passthrough.subtitle_requests(items)
+		} else {
+			Vec::new()
+		}
+	}
+
+	fn set_subtitle(&mut self, item: String, subtitle: Option<String>) {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			passthrough.set_subtitle(item, subtitle);
+		}
+	}
 }
 
 #[derive(Clone, Debug, Default)]
@@ -0,0 +1,146 @@
+use crate::autocomplete::types::{
+	ActiveList,
+	CommandType,
+	Factory,
+	List,
+	Prompt,
+	State,
+};
+use crate::error::reported;
+use crate::path_interpreter::{ ProgramFrequencyMap, read_symbol_frequency };
+use crate::symbols::{ Symbol, get_symbols, search };
+
+// the table has thousands of entries, only this many matches are listed
+const MAX_RESULTS: usize = 100;
+
+pub struct SymbolPickerState {
+	factory: Box<dyn Factory>,
+	fuzzyfind: List,
+	glyphs: Vec<&'static str>, // glyphs of the listed symbols, in the same order as the list
+	preamble: String,
+	search: String,
+	selected: Option<usize>,
+	symbol_frequency: ProgramFrequencyMap,
+	symbols: Vec<Symbol>,
+}
+
+impl Default for SymbolPickerState {
+	fn default() -> Self {
+		let mut state = SymbolPickerState {
+			factory: Box::new(SymbolPickerFactory),
+			fuzzyfind: List::default(),
+			glyphs: Vec::new(),
+			preamble: String::from("emoji "),
+			search: String::default(),
+			selected: None,
+			symbol_frequency: read_symbol_frequency().unwrap_or_else(reported),
+			symbols: get_symbols(),
+		};
+
+		state.update_lists();
+		state
+	}
+}
+
+impl SymbolPickerState {
+	fn update_lists(&mut self) {
+		let matches = search(&self.symbols, &self.symbol_frequency, &self.search, MAX_RESULTS);
+		self.glyphs = matches.iter().map(|symbol| symbol.glyph).collect();
+		self.fuzzyfind = Some(matches.iter().map(|symbol| symbol.label()).collect());
+	}
+
+	// unlike other lists the search is kept while selecting, it is what the list was filtered by
+	fn select(&mut self, index: Option<usize>) -> (String, Option<String>) {
+		self.selected = index;
+		let item = index.and_then(|index| self.fuzzyfind.as_ref()?.get(index).cloned());
+		(self.search.clone(), item)
+	}
+}
+
+impl State for SymbolPickerState {
+	fn get_factory(&self) -> &Box<dyn Factory> {
+		&self.factory
+	}
+
+	fn get_replacement(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_preamble(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_active_list(&self) -> ActiveList {
+		ActiveList::FuzzyFinder
+	}
+
+	fn get_autocomplete_list(&self) -> &List {
+		&None
+	}
+
+	fn get_fuzzyfinder_list(&self) -> &List {
+		&self.fuzzyfind
+	}
+
+	fn update_search(&mut self, search: String) {
+		self.search = search;
+		self.selected = None;
+
+		self.update_lists();
+	}
+
+	fn autocomplete(&mut self) -> (String, Option<String>) {
+		let index = if self.glyphs.is_empty() { None } else { Some(0) };
+		self.select(index)
+	}
+
+	fn get_command(&self) -> (String, Option<String>, CommandType) { // returns the symbol itself
+		let glyph = self.glyphs.get(self.selected.unwrap_or(0)).copied().unwrap_or_default();
+		(glyph.to_string(), None, CommandType::Symbol)
+	}
+
+	fn select_next(&mut self) -> (String, Option<String>) {
+		let index = match self.selected {
+			_ if self.glyphs.is_empty() => None,
+			Some(index) if index + 1 < self.glyphs.len() => Some(index + 1),
+			_ => Some(0),
+		};
+
+		self.select(index)
+	}
+
+	fn select_previous(&mut self) -> (String, Option<String>) {
+		let index = match self.selected {
+			_ if self.glyphs.is_empty() => None,
+			Some(index) if index > 0 => Some(index - 1),
+			_ => Some(self.glyphs.len() - 1),
+		};
+
+		self.select(index)
+	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		let length = self.fuzzyfind.as_ref().map_or(0, |list| list.len());
+		self.select(Some(index).filter(|index| *index < length))
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		Prompt {
+			mode: Some(String::from("emoji")),
+			placeholder: String::new(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SymbolPickerFactory;
+
+impl Factory for SymbolPickerFactory {
+	fn should_create(&self, search: &String) -> bool {
+		search.starts_with("emoji ")
+	}
+
+	fn create(&self) -> Box<dyn State> {
+		Box::new(SymbolPickerState::default())
+	}
+}
@@ -0,0 +1,276 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::autocomplete::types::{
+	ActiveList,
+	CommandType,
+	Factory,
+	List,
+	Prompt,
+	State,
+	UnitAction,
+	UnitScope,
+};
+use crate::config::CONFIG;
+use crate::systemd::{ Unit, get_units, };
+
+// units keep the order systemctl lists them in, which is alphabetical within each scope
+pub struct SystemdState {
+	action_list: List,
+	factory: Box<dyn Factory>,
+	fuzzyfind: List,
+	icons: HashMap<String, &'static str>,
+	labels: Vec<String>,
+	preamble: String,
+	search: String,
+	selected: Option<usize>,
+	showing_actions: bool, // list the actions for the selected unit instead of units
+	subtitles: HashMap<String, String>,
+	units: Vec<(String, Unit)>,
+}
+
+// a unit can exist for the user and the system at the same time, the system one gets marked
+fn label_units(units: Vec<Unit>) -> Vec<(String, Unit)> {
+	let mut labels = HashSet::new();
+	units.into_iter()
+		.map(|unit| {
+			let label = if labels.contains(&unit.name) {
+				format!("{} (system)", unit.name)
+			} else {
+				unit.name.clone()
+			};
+			labels.insert(label.clone());
+
+			(label, unit)
+		})
+		.collect()
+}
+
+fn subtitle(unit: &Unit) -> String {
+	let scope = match unit.scope {
+		UnitScope::User => "user",
+		UnitScope::System => "system",
+	};
+
+	if unit.description.is_empty() {
+		format!("{} ({}) · {}", unit.active, unit.sub, scope)
+	} else {
+		format!("{} ({}) · {} · {}", unit.active, unit.sub, scope, unit.description)
+	}
+}
+
+fn icon(unit: &Unit) -> &'static str {
+	match unit.active.as_str() {
+		"active" => "play_circle",
+		"failed" => "error",
+		"activating" | "deactivating" | "reloading" => "pending",
+		_ => "stop_circle",
+	}
+}
+
+impl Default for SystemdState {
+	fn default() -> Self {
+		let units = label_units(get_units());
+		let mut state = SystemdState {
+			action_list: Some(
+				UnitAction::ALL.iter()
+					.map(|action| action.label().to_string())
+					.collect::<Vec<String>>()
+			),
+			factory: Box::new(SystemdFactory),
+			fuzzyfind: List::default(),
+			icons: units.iter()
+				.map(|(label, unit)| (label.clone(), icon(unit)))
+				.collect(),
+			labels: units.iter().map(|(label, _)| label.clone()).collect(),
+			preamble: String::from("systemd "),
+			search: String::default(),
+			selected: None,
+			showing_actions: false,
+			subtitles: units.iter()
+				.map(|(label, unit)| (label.clone(), subtitle(unit)))
+				.collect(),
+			units,
+		};
+
+		state.update_lists();
+		state
+	}
+}
+
+impl SystemdState {
+	fn update_lists(&mut self) {
+		let search = self.search.to_lowercase();
+		self.fuzzyfind = Some(
+			self.labels.iter()
+				.filter(|label| label.to_lowercase().contains(&search))
+				.cloned()
+				.collect()
+		);
+	}
+
+	fn find_unit(&self) -> Option<&(String, Unit)> {
+		let search = self.search.to_lowercase();
+		self.units.iter()
+			.find(|(label, _)| label == &self.search)
+			.or_else(|| self.units.iter().find(|(label, _)| label.to_lowercase().contains(&search)))
+	}
+
+	fn select(&mut self, index: Option<usize>) -> (String, Option<String>) {
+		self.selected = index;
+		if let Some(index) = index {
+			self.search = self.fuzzyfind.as_ref().unwrap()[index].clone();
+			(self.search.clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+}
+
+impl State for SystemdState {
+	fn get_factory(&self) -> &Box<dyn Factory> {
+		&self.factory
+	}
+
+	fn get_replacement(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_preamble(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_active_list(&self) -> ActiveList {
+		ActiveList::FuzzyFinder
+	}
+
+	fn get_autocomplete_list(&self) -> &List {
+		&None
+	}
+
+	fn get_fuzzyfinder_list(&self) -> &List {
+		if self.showing_actions {
+			&self.action_list
+		} else {
+			&self.fuzzyfind
+		}
+	}
+
+	fn update_search(&mut self, search: String) {
+		self.showing_actions = false;
+		self.search = search;
+		self.selected = None;
+
+		self.update_lists();
+	}
+
+	fn autocomplete(&mut self) -> (String, Option<String>) {
+		self.showing_actions = false;
+		let length = self.fuzzyfind.as_ref().map_or(0, |list| list.len());
+		self.select(if length == 0 { None } else { Some(0) })
+	}
+
+	fn get_command(&self) -> (String, Option<String>, CommandType) { // returns the unit name
+		let action = if self.showing_actions {
+			UnitAction::ALL[self.selected.unwrap_or(0)]
+		} else {
+			CONFIG.systemd.default
+		};
+
+		if let Some((_, unit)) = self.find_unit() {
+			(unit.name.clone(), None, CommandType::Unit(action, unit.scope))
+		} else {
+			(String::new(), None, CommandType::Unit(action, UnitScope::User))
+		}
+	}
+
+	fn select_next(&mut self) -> (String, Option<String>) {
+		if self.showing_actions {
+			let index = (self.selected.unwrap_or(0) + 1) % UnitAction::ALL.len();
+			self.selected = Some(index);
+			return (self.search.clone(), Some(UnitAction::ALL[index].label().to_string()));
+		}
+
+		let length = self.fuzzyfind.as_ref().map_or(0, |list| list.len());
+		let index = match self.selected {
+			_ if length == 0 => None,
+			Some(index) if index + 1 < length => Some(index + 1),
+			_ => Some(0),
+		};
+
+		self.select(index)
+	}
+
+	fn select_previous(&mut self) -> (String, Option<String>) {
+		if self.showing_actions {
+			let index = (self.selected.unwrap_or(0) + UnitAction::ALL.len() - 1) % UnitAction::ALL.len();
+			self.selected = Some(index);
+			return (self.search.clone(), Some(UnitAction::ALL[index].label().to_string()));
+		}
+
+		let length = self.fuzzyfind.as_ref().map_or(0, |list| list.len());
+		let index = match self.selected {
+			_ if length == 0 => None,
+			Some(index) if index > 0 => Some(index - 1),
+			_ => Some(length - 1),
+		};
+
+		self.select(index)
+	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		if self.showing_actions {
+			let index = index.min(UnitAction::ALL.len() - 1);
+			self.selected = Some(index);
+			return (self.search.clone(), Some(UnitAction::ALL[index].label().to_string()));
+		}
+
+		let length = self.fuzzyfind.as_ref().map_or(0, |list| list.len());
+		self.select(Some(index).filter(|index| *index < length))
+	}
+
+	fn show_actions(&mut self) -> Option<(String, Option<String>)> {
+		if !self.units.iter().any(|(label, _)| label == &self.search) {
+			return None;
+		}
+
+		self.showing_actions = true;
+		self.selected = Some(0);
+		Some((self.search.clone(), Some(UnitAction::ALL[0].label().to_string())))
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		Prompt {
+			mode: Some(String::from("systemd")),
+			placeholder: String::new(),
+		}
+	}
+
+	fn get_icon(&self, item: &String) -> Option<&str> {
+		if self.showing_actions {
+			return None;
+		}
+
+		self.icons.get(item).copied()
+	}
+
+	fn get_subtitle(&self, item: &String) -> Option<&String> {
+		if self.showing_actions {
+			return None;
+		}
+
+		self.subtitles.get(item)
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SystemdFactory;
+
+impl Factory for SystemdFactory {
+	fn should_create(&self, search: &String) -> bool {
+		search.starts_with("systemd ")
+	}
+
+	fn create(&self) -> Box<dyn State> {
+		Box::new(SystemdState::default())
+	}
+}
@@ -1,5 +1,31 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::autocomplete::window_switcher::WindowEntry;
+
 pub type List = Option<Vec<String>>;
 
+// material icons shown for each kind of entry when the icon theme has nothing better
+pub const PROGRAM_ICON: &str = "apps";
+pub const SHELL_ICON: &str = "terminal";
+pub const WINDOW_ICON: &str = "web_asset";
+pub const PROJECT_ICON: &str = "folder";
+pub const PROCESS_ICON: &str = "memory";
+pub const HOST_ICON: &str = "dns";
+
+// runs on a blocking thread and produces the subtitle for a list item
+pub type SubtitleLoader = Box<dyn FnOnce() -> Option<String> + Send>;
+
+// runs on a blocking thread and finds when each project was last committed to
+pub type CommitTimesLoader = Box<dyn FnOnce() -> HashMap<String, u64> + Send>;
+
+// how the search bar presents a state
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Prompt {
+	pub mode: Option<String>, // named in a chip in front of the search bar, e.g. "sudo"
+	pub placeholder: String,  // shown while nothing has been typed, which never happens inside of a mode
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Autocomplete {
 	pub common_start: String,
@@ -8,8 +34,129 @@ pub struct Autocomplete {
 
 pub enum CommandType {
 	Normal,
-	OpenProject,
+	OpenProject(ProjectAction),
+	FocusWindow,
+	Calculation,
+	ClipboardEntry,
+	Custom,
+	Session(SessionAction),
 	Sudo,
+	Symbol,
+	Unit(UnitAction, UnitScope),
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectAction {
+	#[default]
+	Editor,
+	Terminal,
+	FileManager,
+	GitStatus,
+}
+
+impl ProjectAction {
+	pub const ALL: [ProjectAction; 4] = [
+		ProjectAction::Editor,
+		ProjectAction::Terminal,
+		ProjectAction::FileManager,
+		ProjectAction::GitStatus,
+	];
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			ProjectAction::Editor => "open in editor",
+			ProjectAction::Terminal => "open terminal",
+			ProjectAction::FileManager => "open file manager",
+			ProjectAction::GitStatus => "git status",
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SessionAction {
+	Lock,
+	Suspend,
+	Hibernate,
+	Reboot,
+	Poweroff,
+	Logout,
+}
+
+impl SessionAction {
+	pub const ALL: [SessionAction; 6] = [
+		SessionAction::Lock,
+		SessionAction::Suspend,
+		SessionAction::Hibernate,
+		SessionAction::Reboot,
+		SessionAction::Poweroff,
+		SessionAction::Logout,
+	];
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			SessionAction::Lock => "lock",
+			SessionAction::Suspend => "suspend",
+			SessionAction::Hibernate => "hibernate",
+			SessionAction::Reboot => "reboot",
+			SessionAction::Poweroff => "power off",
+			SessionAction::Logout => "log out",
+		}
+	}
+
+	// name of the glyph in the bundled material icons font
+	pub fn icon(&self) -> &'static str {
+		match self {
+			SessionAction::Lock => "lock",
+			SessionAction::Suspend => "bedtime",
+			SessionAction::Hibernate => "ac_unit",
+			SessionAction::Reboot => "restart_alt",
+			SessionAction::Poweroff => "power_settings_new",
+			SessionAction::Logout => "logout",
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitAction {
+	Start,
+	Stop,
+	Restart,
+	#[default]
+	Status,
+	Journal,
+}
+
+impl UnitAction {
+	pub const ALL: [UnitAction; 5] = [
+		UnitAction::Status,
+		UnitAction::Start,
+		UnitAction::Stop,
+		UnitAction::Restart,
+		UnitAction::Journal,
+	];
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			UnitAction::Start => "start",
+			UnitAction::Stop => "stop",
+			UnitAction::Restart => "restart",
+			UnitAction::Status => "status",
+			UnitAction::Journal => "journal",
+		}
+	}
+
+	// actions that change the state of the unit, only these need root for system units
+	pub fn is_privileged(&self) -> bool {
+		matches!(self, UnitAction::Start | UnitAction::Stop | UnitAction::Restart)
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UnitScope {
+	User,
+	System,
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -50,8 +197,59 @@ pub trait State {
 	fn autocomplete(&mut self) -> (String, Option<String>);
 	fn get_command(&self) -> (String, Option<String>, CommandType);
 
-	fn select_up(&mut self) -> (String, Option<String>);
-	fn select_down(&mut self) -> (String, Option<String>);
+	// move towards the end and the start of the list, wrapping around at either end
+	fn select_next(&mut self) -> (String, Option<String>);
+	fn select_previous(&mut self) -> (String, Option<String>);
+	// selects a row of the ui list directly, for clicks and jumps to the start, end or a page away
+	fn select_index(&mut self, index: usize) -> (String, Option<String>);
+
+	// switches the list to the secondary actions for the current item, if the state has any
+	fn show_actions(&mut self) -> Option<(String, Option<String>)> {
+		None
+	}
+
+	// name of the material icon shown in front of an item, used when it has no themed icon
+	fn get_icon(&self, _item: &String) -> Option<&str> {
+		None
+	}
+
+	// freedesktop icon name, program or path that the themed icon of an item is looked up by
+	fn get_icon_name(&self, _item: &String) -> Option<String> {
+		None
+	}
+
+	// the mode this state is and a hint at what to type, states with a passthrough name both
+	fn get_prompt(&self) -> Prompt {
+		Prompt::default()
+	}
+
+	// second line shown under an item in the list
+	fn get_subtitle(&self, _item: &String) -> Option<&String> {
+		None
+	}
+
+	// loaders for the subtitles of the given visible items that have not been requested yet
+	fn subtitle_requests(&mut self, _items: &[String]) -> Vec<(String, SubtitleLoader)> {
+		Vec::new()
+	}
+
+	fn set_subtitle(&mut self, _item: String, _subtitle: Option<String>) {}
+
+	// asking the compositor for its windows takes a while, so they arrive after the state is made
+	fn set_windows(&mut self, _windows: Vec<WindowEntry>) {}
+
+	// reading the reflog of every project takes a while too, a state hands out the work only once
+	fn commit_times_request(&mut self) -> Option<CommitTimesLoader> {
+		None
+	}
+
+	fn set_commit_times(&mut self, _commit_times: HashMap<String, u64>) {}
+
+	// removes the selected item for states that keep a history, returning the new search like
+	// select_next does
+	fn delete_selected(&mut self) -> Option<(String, Option<String>)> {
+		None
+	}
 
 	// the list that we'll use in the ui
 	fn get_ui_list(&self) -> &List {
@@ -101,6 +299,21 @@ pub fn passthrough_string(
 	}
 }
 
+// a mode inside of another one is named after both of them, e.g. "sudo killall"
+pub fn passthrough_prompt(mode: &str, passthrough: &Option<Box<dyn State>>) -> Prompt {
+	let inner = passthrough
+		.as_ref()
+		.and_then(|passthrough| passthrough.get_prompt().mode);
+
+	Prompt {
+		mode: Some(match inner {
+			Some(inner) => format!("{} {}", mode, inner),
+			None => mode.to_string(),
+		}),
+		placeholder: String::new(),
+	}
+}
+
 pub fn passthrough_command(
 	search: &String,
 	base_command: &String,
@@ -116,11 +329,28 @@ pub fn passthrough_command(
 			passthrough_base_command
 		};
 
-		(
-			format!("{}{}", search, passthrough_command),
-			passthrough_base_command,
-			passthrough_command_type,
-		)
+		if let CommandType::OpenProject(_)
+		| CommandType::FocusWindow
+		| CommandType::Calculation
+		| CommandType::ClipboardEntry
+		| CommandType::Session(_)
+		| CommandType::Symbol
+		| CommandType::Unit(_, _) = passthrough_command_type
+		{
+			// project paths, window ids, values, entry ids, symbols and unit names are not something
+			// that can be prefixed
+			(
+				passthrough_command,
+				passthrough_base_command,
+				passthrough_command_type,
+			)
+		} else {
+			(
+				format!("{}{}", search, passthrough_command),
+				passthrough_base_command,
+				passthrough_command_type,
+			)
+		}
 	} else {
 		(search.clone(), Some(base_command.clone()), command_type)
 	}
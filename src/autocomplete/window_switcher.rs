@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+
+use crate::autocomplete::program_sorting::autocomplete;
+use crate::autocomplete::types::{
+	ActiveList,
+	Autocomplete,
+	CommandType,
+	Factory,
+	List,
+	Prompt,
+	State,
+	WINDOW_ICON,
+	get_ui_list,
+};
+use crate::compositor::{ Window, get_windows };
+use crate::path_interpreter::ProgramFrequencyMap;
+
+#[derive(Clone, Debug)]
+pub struct WindowEntry {
+	pub label: String,
+	pub subtitle: String,
+	pub window: Window,
+}
+
+impl WindowEntry {
+	// the label used when the window is listed next to programs in the default list
+	pub fn switch_label(&self) -> String {
+		format!("switch to {}", self.label)
+	}
+}
+
+// labels are what the ui selects by, so windows with the same title get numbered
+pub fn get_window_entries() -> Vec<WindowEntry> {
+	let mut labels = HashSet::new();
+	get_windows().into_iter()
+		.map(|window| {
+			let title = if window.title.is_empty() { window.app_id.clone() } else { window.title.clone() };
+
+			let mut label = title.clone();
+			let mut count = 1;
+			while labels.contains(&label) {
+				count += 1;
+				label = format!("{} ({})", title, count);
+			}
+			labels.insert(label.clone());
+
+			let subtitle = if let Some(workspace) = window.workspace.as_ref() {
+				format!("{} · workspace {}", window.app_id, workspace)
+			} else {
+				window.app_id.clone()
+			};
+
+			WindowEntry {
+				label,
+				subtitle,
+				window,
+			}
+		})
+		.collect()
+}
+
+pub fn fuzzyfind_windows<'a>(windows: &'a [WindowEntry], search: &str) -> Vec<&'a WindowEntry> {
+	let search = search.to_lowercase();
+	windows.iter()
+		.filter(|entry| {
+			entry.label.to_lowercase().contains(&search)
+				|| entry.window.app_id.to_lowercase().contains(&search)
+		})
+		.collect()
+}
+
+pub struct WindowSwitcherState {
+	active_list: ActiveList,
+	autocomplete: Option<Autocomplete>,
+	factory: Box<dyn Factory>,
+	fuzzyfind: List,
+	labels: Vec<String>,
+	preamble: String,
+	search: String,
+	selected: Option<usize>,
+	windows: Vec<WindowEntry>,
+}
+
+impl Default for WindowSwitcherState {
+	fn default() -> Self {
+		let windows = get_window_entries();
+		WindowSwitcherState {
+			active_list: ActiveList::default(),
+			autocomplete: None,
+			factory: Box::new(WindowSwitcherFactory),
+			fuzzyfind: List::default(),
+			labels: windows.iter().map(|entry| entry.label.clone()).collect(),
+			preamble: String::from("window "),
+			search: String::default(),
+			selected: None,
+			windows,
+		}
+	}
+}
+
+impl WindowSwitcherState {
+	fn update_lists(&mut self) {
+		self.autocomplete = autocomplete(&self.labels, &ProgramFrequencyMap::default(), &self.search);
+		self.fuzzyfind = Some(
+			fuzzyfind_windows(&self.windows, &self.search).iter()
+				.map(|entry| entry.label.clone())
+				.collect()
+		);
+	}
+}
+
+impl State for WindowSwitcherState {
+	fn get_factory(&self) -> &Box<dyn Factory> {
+		&self.factory
+	}
+
+	fn get_replacement(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_preamble(&self) -> &String {
+		&self.preamble
+	}
+
+	fn get_active_list(&self) -> ActiveList {
+		self.active_list
+	}
+
+	fn get_autocomplete_list(&self) -> &List {
+		if let Some(autocomplete) = self.autocomplete.as_ref() {
+			&autocomplete.list
+		} else {
+			&None
+		}
+	}
+
+	fn get_fuzzyfinder_list(&self) -> &List {
+		&self.fuzzyfind
+	}
+
+	fn update_search(&mut self, search: String) {
+		self.search = search;
+		self.active_list = ActiveList::FuzzyFinder;
+		self.selected = None;
+
+		self.update_lists();
+	}
+
+	fn autocomplete(&mut self) -> (String, Option<String>) {
+		self.active_list = ActiveList::Autocomplete;
+		if let Some(list) = self.autocomplete.as_ref() && !list.common_start.is_empty() {
+			self.search = list.common_start.clone();
+		}
+
+		self.update_lists();
+
+		if !self.search.is_empty() {
+			self.selected = Some(0);
+		}
+
+		(self.search.clone(), None)
+	}
+
+	fn get_command(&self) -> (String, Option<String>, CommandType) { // returns the window id
+		// fall back to the best match so a partial title is enough to switch
+		let entry = self.windows.iter()
+			.find(|entry| entry.label == self.search)
+			.or_else(|| fuzzyfind_windows(&self.windows, &self.search).first().copied());
+
+		if let Some(entry) = entry {
+			(entry.window.id.clone(), None, CommandType::FocusWindow)
+		} else {
+			(String::new(), None, CommandType::FocusWindow)
+		}
+	}
+
+	fn select_next(&mut self) -> (String, Option<String>) {
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		if list.is_none_or(|list| list.is_empty()) {
+			self.selected = None;
+		} else if self.selected.is_some() && self.selected.unwrap() != list.unwrap().len() - 1 {
+			self.selected = Some(self.selected.unwrap() + 1);
+		} else {
+			self.selected = Some(0);
+		}
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn select_previous(&mut self) -> (String, Option<String>) {
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		if list.is_none_or(|list| list.is_empty()) {
+			self.selected = None;
+		} else if self.selected.is_some() && self.selected.unwrap() != 0 {
+			self.selected = Some(self.selected.unwrap() - 1);
+		} else {
+			self.selected = Some(list.unwrap().len() - 1);
+		}
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		self.selected = list.filter(|list| index < list.len()).map(|_| index);
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		Prompt {
+			mode: Some(String::from("window")),
+			placeholder: String::new(),
+		}
+	}
+
+	fn get_icon(&self, _item: &String) -> Option<&str> {
+		Some(WINDOW_ICON)
+	}
+
+	fn get_icon_name(&self, item: &String) -> Option<String> {
+		self.windows.iter()
+			.find(|entry| &entry.label == item)
+			.map(|entry| entry.window.app_id.clone())
+	}
+
+	fn get_subtitle(&self, item: &String) -> Option<&String> {
+		self.windows.iter()
+			.find(|entry| &entry.label == item)
+			.map(|entry| &entry.subtitle)
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WindowSwitcherFactory;
+
+impl Factory for WindowSwitcherFactory {
+	fn should_create(&self, search: &String) -> bool {
+		search.starts_with("window ")
+	}
+
+	fn create(&self) -> Box<dyn State> {
+		Box::new(WindowSwitcherState::default())
+	}
+}
@@ -4,13 +4,17 @@ use crate::autocomplete::types::{
 	Autocomplete,
 	CommandType,
 	Factory,
+	HOST_ICON,
 	List,
+	Prompt,
 	State,
 	get_ui_list,
 	handle_update_placeholder,
 	passthrough_command,
+	passthrough_prompt,
 	passthrough_string,
 };
+use crate::error::reported;
 use crate::path_interpreter::{
 	ProgramFrequencyMap,
 	get_programs,
@@ -43,7 +47,7 @@ impl Default for X11ForwardState {
 			passthrough_factories: vec![],
 			preamble: String::from("ssh me@$(ping bansheestation -c 1 -q -W 1 | grep -q \"1 received\" && echo \"bansheestation\" || echo \"bansheestation-alt\") "),
 			programs: get_programs().unwrap(),
-			program_frequency: read_command_frequency(),
+			program_frequency: read_command_frequency().unwrap_or_else(reported),
 			replacement: String::from("!"),
 			search: String::default(),
 			selected: None,
@@ -140,13 +144,13 @@ impl State for X11ForwardState {
 		)
 	}
 
-	fn select_up(&mut self) -> (String, Option<String>) {
+	fn select_next(&mut self) -> (String, Option<String>) {
 		if let Some(passthrough) = self.passthrough.as_mut() {
-			return passthrough_string(&self.search, passthrough.select_up());
+			return passthrough_string(&self.search, passthrough.select_next());
 		}
 
 		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != list.unwrap().len() - 1 {
 			self.selected = Some(self.selected.unwrap() + 1);
@@ -162,13 +166,13 @@ impl State for X11ForwardState {
 		}
 	}
 
-	fn select_down(&mut self) -> (String, Option<String>) {
+	fn select_previous(&mut self) -> (String, Option<String>) {
 		if let Some(passthrough) = self.passthrough.as_mut() {
-			return passthrough_string(&self.search, passthrough.select_down());
+			return passthrough_string(&self.search, passthrough.select_previous());
 		}
 
 		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
-		if let None = list {
+		if list.is_none_or(|list| list.is_empty()) {
 			self.selected = None;
 		} else if self.selected.is_some() && self.selected.unwrap() != 0 {
 			self.selected = Some(self.selected.unwrap() - 1);
@@ -183,6 +187,31 @@ impl State for X11ForwardState {
 			(String::new(), None)
 		}
 	}
+
+	fn select_index(&mut self, index: usize) -> (String, Option<String>) {
+		if let Some(passthrough) = self.passthrough.as_mut() {
+			return passthrough_string(&self.search, passthrough.select_index(index));
+		}
+
+		let list = get_ui_list(&self.active_list, &self.autocomplete, &self.fuzzyfind).as_ref();
+		self.selected = list.filter(|list| index < list.len()).map(|_| index);
+
+		if let Some(index) = self.selected {
+			self.search = list.unwrap()[index].clone();
+			(list.unwrap()[index].clone(), None)
+		} else {
+			(String::new(), None)
+		}
+	}
+
+	fn get_prompt(&self) -> Prompt {
+		passthrough_prompt("ssh bansheestation", &self.passthrough)
+	}
+
+	// programs run on the remote host, so the local icon theme would only be guessing
+	fn get_icon(&self, _item: &String) -> Option<&str> {
+		Some(HOST_ICON)
+	}
 }
 
 #[derive(Clone, Debug, Default)]
@@ -0,0 +1,764 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::config::{create_data_directory, data_directory};
+use crate::error::{report, Error};
+use units::{dimension_name, find_unit, format_base_unit, Dimension, Unit, DIMENSIONLESS};
+
+mod units;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+	Number(f64),
+	Identifier(String),
+	Operator(char),
+	LeftParenthesis,
+	RightParenthesis,
+	Comma,
+}
+
+impl fmt::Display for Token {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Token::Number(number) => write!(f, "{}", number),
+			Token::Identifier(identifier) => write!(f, "{}", identifier),
+			Token::Operator(operator) => write!(f, "'{}'", operator),
+			Token::LeftParenthesis => write!(f, "'('"),
+			Token::RightParenthesis => write!(f, "')'"),
+			Token::Comma => write!(f, "','"),
+		}
+	}
+}
+
+fn lex_radix(chars: &mut Peekable<Chars>, radix: u32) -> Result<Token, String> {
+	let mut digits = String::new();
+	while let Some(&character) = chars.peek() {
+		if character.is_digit(radix) {
+			digits.push(character);
+		} else if character != '_' {
+			break;
+		}
+
+		chars.next();
+	}
+
+	if digits.is_empty() {
+		return Err(String::from("missing digits after base prefix"));
+	}
+
+	u64::from_str_radix(&digits, radix)
+		.map(|number| Token::Number(number as f64))
+		.map_err(|_| format!("{} is too large", digits))
+}
+
+fn lex_decimal(chars: &mut Peekable<Chars>) -> Result<Token, String> {
+	let mut digits = String::new();
+	while let Some(&character) = chars.peek() {
+		if character.is_ascii_digit() || character == '.' {
+			digits.push(character);
+		} else if (character == 'e' || character == 'E') && !digits.contains('e') {
+			// only an exponent if a number follows, otherwise it is the constant e
+			let mut lookahead = chars.clone();
+			lookahead.next();
+			if let Some(&next) = lookahead.peek()
+				&& (next.is_ascii_digit() || next == '-' || next == '+')
+			{
+				digits.push('e');
+				chars.next();
+				digits.push(chars.next().unwrap());
+				continue;
+			}
+
+			break;
+		} else if character != '_' {
+			break;
+		}
+
+		chars.next();
+	}
+
+	digits.parse().map(Token::Number).map_err(|_| format!("invalid number {}", digits))
+}
+
+fn lex(expression: &str) -> Result<Vec<Token>, String> {
+	let mut tokens = Vec::new();
+	let mut chars = expression.chars().peekable();
+	while let Some(&character) = chars.peek() {
+		if character.is_whitespace() {
+			chars.next();
+		} else if character == '0' {
+			let mut lookahead = chars.clone();
+			lookahead.next();
+			let radix = match lookahead.peek() {
+				Some('x') | Some('X') => Some(16),
+				Some('b') | Some('B') => Some(2),
+				Some('o') | Some('O') => Some(8),
+				_ => None,
+			};
+
+			if let Some(radix) = radix {
+				chars.next();
+				chars.next();
+				tokens.push(lex_radix(&mut chars, radix)?);
+			} else {
+				tokens.push(lex_decimal(&mut chars)?);
+			}
+		} else if character.is_ascii_digit() || character == '.' {
+			tokens.push(lex_decimal(&mut chars)?);
+		} else if character.is_alphabetic() || character == '_' || character == '°' {
+			let mut identifier = String::new();
+			while let Some(&character) = chars.peek() {
+				if !character.is_alphanumeric() && character != '_' && character != '°' {
+					break;
+				}
+
+				identifier.push(character);
+				chars.next();
+			}
+
+			tokens.push(Token::Identifier(identifier));
+		} else {
+			chars.next();
+			tokens.push(match character {
+				'+' | '-' | '*' | '/' | '%' | '^' | '!' => Token::Operator(character),
+				'×' => Token::Operator('*'),
+				'÷' => Token::Operator('/'),
+				'(' => Token::LeftParenthesis,
+				')' => Token::RightParenthesis,
+				',' => Token::Comma,
+				_ => return Err(format!("unexpected '{}'", character)),
+			});
+		}
+	}
+
+	Ok(tokens)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Quantity {
+	pub value: f64, // in base units
+	pub dimension: Dimension,
+}
+
+impl Quantity {
+	fn number(value: f64) -> Self {
+		Quantity {
+			value,
+			dimension: DIMENSIONLESS,
+		}
+	}
+
+	fn require_number(self, what: &str) -> Result<f64, String> {
+		if self.dimension == DIMENSIONLESS {
+			Ok(self.value)
+		} else {
+			Err(format!("{} needs a plain number, not {}", what, dimension_name(self.dimension)))
+		}
+	}
+}
+
+fn add(left: Quantity, right: Quantity, operator: char) -> Result<Quantity, String> {
+	if left.dimension != right.dimension {
+		let verb = match operator {
+			'-' => "subtract",
+			'%' => "take the remainder of",
+			_ => "add",
+		};
+
+		return Err(format!(
+			"cannot {} {} and {}",
+			verb,
+			dimension_name(left.dimension),
+			dimension_name(right.dimension)
+		));
+	}
+
+	let value = if operator == '-' { left.value - right.value } else { left.value + right.value };
+	Ok(Quantity {
+		value,
+		dimension: left.dimension,
+	})
+}
+
+fn combine(left: Dimension, right: Dimension, sign: i8) -> Result<Dimension, String> {
+	let mut dimension = left;
+	for (exponent, other) in dimension.iter_mut().zip(right) {
+		*exponent = other
+			.checked_mul(sign)
+			.and_then(|other| exponent.checked_add(other))
+			.ok_or_else(|| String::from("exponent too large"))?;
+	}
+
+	Ok(dimension)
+}
+
+fn factorial(number: f64) -> Result<f64, String> {
+	if number < 0.0 || number.fract() != 0.0 {
+		return Err(String::from("factorial needs a whole number"));
+	}
+
+	// 171! is already too large for an f64, counting up to a huge number would freeze the window
+	if number > 170.0 {
+		return Ok(f64::INFINITY);
+	}
+
+	Ok((1..=number as u64).fold(1.0, |acc, value| acc * value as f64))
+}
+
+fn call(function: &str, arguments: &[f64]) -> Result<f64, String> {
+	let one = |f: fn(f64) -> f64| {
+		if let [value] = arguments {
+			Ok(f(*value))
+		} else {
+			Err(format!("{} takes one argument", function))
+		}
+	};
+
+	let two = |f: fn(f64, f64) -> f64| {
+		if let [a, b] = arguments {
+			Ok(f(*a, *b))
+		} else {
+			Err(format!("{} takes two arguments", function))
+		}
+	};
+
+	match function {
+		"sqrt" => one(f64::sqrt),
+		"cbrt" => one(f64::cbrt),
+		"abs" => one(f64::abs),
+		"sin" => one(f64::sin),
+		"cos" => one(f64::cos),
+		"tan" => one(f64::tan),
+		"asin" => one(f64::asin),
+		"acos" => one(f64::acos),
+		"atan" => one(f64::atan),
+		"sinh" => one(f64::sinh),
+		"cosh" => one(f64::cosh),
+		"tanh" => one(f64::tanh),
+		"exp" => one(f64::exp),
+		"ln" => one(f64::ln),
+		"log" | "log10" => one(f64::log10),
+		"log2" => one(f64::log2),
+		"floor" => one(f64::floor),
+		"ceil" => one(f64::ceil),
+		"round" => one(f64::round),
+		"trunc" => one(f64::trunc),
+		"deg" => one(f64::to_degrees),
+		"rad" => one(f64::to_radians),
+		"pow" => two(f64::powf),
+		"atan2" => two(f64::atan2),
+		"hypot" => two(f64::hypot),
+		"min" | "max" => {
+			if arguments.is_empty() {
+				return Err(format!("{} needs at least one argument", function));
+			}
+
+			let fold = if function == "min" { f64::min } else { f64::max };
+			Ok(arguments.iter().copied().reduce(fold).unwrap())
+		}
+		_ => Err(format!("unknown function {}", function)),
+	}
+}
+
+fn constant(name: &str, ans: Option<f64>) -> Option<Result<f64, String>> {
+	match name {
+		"pi" | "π" => Some(Ok(std::f64::consts::PI)),
+		"tau" => Some(Ok(std::f64::consts::TAU)),
+		"e" => Some(Ok(std::f64::consts::E)),
+		"phi" => Some(Ok(1.618_033_988_749_895)),
+		"ans" => Some(ans.ok_or(String::from("no previous answer"))),
+		_ => None,
+	}
+}
+
+struct Parser<'a> {
+	ans: Option<f64>,
+	index: usize,
+	preferred: Option<Dimension>, // decides ambiguous unit names like m
+	tokens: &'a [Token],
+}
+
+impl Parser<'_> {
+	fn peek(&self) -> Option<&Token> {
+		self.tokens.get(self.index)
+	}
+
+	fn next(&mut self) -> Option<&Token> {
+		self.index += 1;
+		self.tokens.get(self.index - 1)
+	}
+
+	fn expect(&mut self, token: Token) -> Result<(), String> {
+		match self.next() {
+			Some(next) if *next == token => Ok(()),
+			Some(next) => Err(format!("expected {}, found {}", token, next)),
+			None => Err(String::from("unexpected end of expression")),
+		}
+	}
+
+	// a unit written directly after a number, as long as the name is not a function or constant
+	fn unit_at(&self, index: usize) -> Option<&'static Unit> {
+		if let Some(Token::Identifier(name)) = self.tokens.get(index)
+			&& self.tokens.get(index + 1) != Some(&Token::LeftParenthesis)
+			&& constant(&name.to_lowercase(), None).is_none()
+		{
+			find_unit(name, self.preferred)
+		} else {
+			None
+		}
+	}
+
+	// expression := term (('+' | '-') term)*
+	fn expression(&mut self) -> Result<Quantity, String> {
+		let mut value = self.term()?;
+		while let Some(Token::Operator(operator @ ('+' | '-'))) = self.peek() {
+			let operator = *operator;
+			self.next();
+			let right = self.term()?;
+			value = add(value, right, operator)?;
+		}
+
+		Ok(value)
+	}
+
+	// term := unary (('*' | '/' | '%') unary | unary)*, a missing operator is multiplication except
+	// between quantities with units like 3h 20m, which are added
+	fn term(&mut self) -> Result<Quantity, String> {
+		let mut value = self.unary()?;
+		loop {
+			let operator = match self.peek() {
+				Some(Token::Operator(operator @ ('*' | '/' | '%'))) => {
+					let operator = *operator;
+					self.next();
+					operator
+				}
+				Some(Token::Number(_)) if value.dimension != DIMENSIONLESS => '+',
+				Some(Token::Number(_)) | Some(Token::Identifier(_)) | Some(Token::LeftParenthesis) => '*',
+				_ => break,
+			};
+
+			if operator == '+' {
+				let preferred = self.preferred;
+				self.preferred = Some(value.dimension);
+				let right = self.unary();
+				self.preferred = preferred;
+				value = add(value, right?, operator)?;
+				continue;
+			}
+
+			let right = self.unary()?;
+			value = match operator {
+				'*' => Quantity {
+					value: value.value * right.value,
+					dimension: combine(value.dimension, right.dimension, 1)?,
+				},
+				'/' => Quantity {
+					value: value.value / right.value,
+					dimension: combine(value.dimension, right.dimension, -1)?,
+				},
+				_ => {
+					let mut remainder = add(value, right, '%')?;
+					remainder.value = value.value % right.value;
+					remainder
+				}
+			};
+		}
+
+		Ok(value)
+	}
+
+	// unary := ('-' | '+') unary | power
+	fn unary(&mut self) -> Result<Quantity, String> {
+		match self.peek() {
+			Some(Token::Operator('-')) => {
+				self.next();
+
+				// a negative temperature has to be negated before its zero point is applied
+				if let Some(&Token::Number(number)) = self.peek()
+					&& let Some(unit) = self.unit_at(self.index + 1)
+					&& unit.offset != 0.0
+				{
+					self.index += 2;
+					return Ok(Quantity {
+						value: unit.to_base(-number),
+						dimension: unit.dimension,
+					});
+				}
+
+				let mut value = self.unary()?;
+				value.value = -value.value;
+				Ok(value)
+			}
+			Some(Token::Operator('+')) => {
+				self.next();
+				self.unary()
+			}
+			_ => self.power(),
+		}
+	}
+
+	// power := postfix ('^' unary)?, which makes ^ right associative
+	fn power(&mut self) -> Result<Quantity, String> {
+		let base = self.postfix()?;
+		if let Some(Token::Operator('^')) = self.peek() {
+			self.next();
+			let exponent = self.unary()?.require_number("an exponent")?;
+			if base.dimension != DIMENSIONLESS && exponent.fract() != 0.0 {
+				return Err(String::from("units can only be raised to whole powers"));
+			}
+
+			// exponents of units are kept in an i8, casting would quietly turn m^200 into m^127
+			let mut dimension = base.dimension;
+			if base.dimension != DIMENSIONLESS {
+				if exponent.abs() > i8::MAX as f64 {
+					return Err(String::from("exponent too large"));
+				}
+
+				for part in dimension.iter_mut() {
+					*part = part
+						.checked_mul(exponent as i8)
+						.ok_or_else(|| String::from("exponent too large"))?;
+				}
+			}
+
+			Ok(Quantity {
+				value: base.value.powf(exponent),
+				dimension,
+			})
+		} else {
+			Ok(base)
+		}
+	}
+
+	// postfix := primary '!'*
+	fn postfix(&mut self) -> Result<Quantity, String> {
+		let mut value = self.primary()?;
+		while let Some(Token::Operator('!')) = self.peek() {
+			self.next();
+			value = Quantity::number(factorial(value.require_number("factorial")?)?);
+		}
+
+		Ok(value)
+	}
+
+	// primary := number unit? | constant | unit | function '(' arguments ')' | '(' expression ')'
+	fn primary(&mut self) -> Result<Quantity, String> {
+		match self.next().cloned() {
+			Some(Token::Number(number)) => {
+				if let Some(unit) = self.unit_at(self.index) {
+					self.next();
+					Ok(Quantity {
+						value: unit.to_base(number),
+						dimension: unit.dimension,
+					})
+				} else {
+					Ok(Quantity::number(number))
+				}
+			}
+			Some(Token::LeftParenthesis) => {
+				let value = self.expression()?;
+				self.expect(Token::RightParenthesis)?;
+				Ok(value)
+			}
+			Some(Token::Identifier(identifier)) => {
+				let lowercase = identifier.to_lowercase();
+				if let Some(Token::LeftParenthesis) = self.peek() {
+					self.next();
+					let mut arguments = Vec::new();
+					if let Some(Token::RightParenthesis) = self.peek() {
+						self.next();
+					} else {
+						loop {
+							arguments.push(self.expression()?.require_number(&lowercase)?);
+							match self.next() {
+								Some(Token::Comma) => continue,
+								Some(Token::RightParenthesis) => break,
+								_ => return Err(String::from("expected ',' or ')'")),
+							}
+						}
+					}
+
+					return call(&lowercase, &arguments).map(Quantity::number);
+				}
+
+				if let Some(value) = constant(&lowercase, self.ans) {
+					return value.map(Quantity::number);
+				}
+
+				// a bare unit is a difference of one unit, so its zero point does not apply
+				if let Some(unit) = find_unit(&identifier, self.preferred) {
+					return Ok(Quantity {
+						value: unit.scale,
+						dimension: unit.dimension,
+					});
+				}
+
+				Err(format!("unknown name {}", identifier))
+			}
+			Some(token) => Err(format!("unexpected {}", token)),
+			None => Err(String::from("unexpected end of expression")),
+		}
+	}
+}
+
+fn evaluate(
+	expression: &str,
+	ans: Option<f64>,
+	preferred: Option<Dimension>,
+) -> Result<Quantity, String> {
+	let tokens = lex(expression)?;
+	let mut parser = Parser {
+		ans,
+		index: 0,
+		preferred,
+		tokens: &tokens,
+	};
+
+	let value = parser.expression()?;
+	if let Some(token) = parser.peek() {
+		return Err(format!("unexpected {}", token));
+	}
+
+	Ok(value)
+}
+
+// splits "10 MiB in KB" into the expression and what to convert it to
+fn split_conversion(input: &str) -> (&str, Option<&str>) {
+	let index = [" in ", " to "]
+		.iter()
+		.filter_map(|separator| input.rfind(separator))
+		.max();
+
+	if let Some(index) = index {
+		let target = input[index + 4..].trim();
+		if !target.is_empty() {
+			return (&input[..index], Some(target));
+		}
+	}
+
+	(input, None)
+}
+
+fn base_radix(target: &str) -> Option<(u32, &'static str)> {
+	match target.to_lowercase().as_str() {
+		"bin" | "binary" => Some((2, "0b")),
+		"oct" | "octal" => Some((8, "0o")),
+		"dec" | "decimal" => Some((10, "")),
+		"hex" | "hexadecimal" => Some((16, "0x")),
+		_ => None,
+	}
+}
+
+fn format_radix(value: f64, radix: u32, prefix: &str) -> Result<Vec<String>, String> {
+	if value.fract() != 0.0 || value.abs() >= u64::MAX as f64 {
+		return Err(String::from("only whole numbers can change base"));
+	}
+
+	let sign = if value < 0.0 { "-" } else { "" };
+	let mut magnitude = value.abs() as u64;
+	let mut digits = Vec::new();
+	loop {
+		digits.push(std::char::from_digit((magnitude % radix as u64) as u32, radix).unwrap());
+		magnitude /= radix as u64;
+		if magnitude == 0 {
+			break;
+		}
+	}
+
+	let digits = digits.iter().rev().collect::<String>();
+	if prefix.is_empty() {
+		Ok(vec![format!("{}{}", sign, digits)])
+	} else {
+		Ok(vec![format!("{}{}{}", sign, prefix, digits), format!("{}{}", sign, digits)])
+	}
+}
+
+fn convert(quantity: Quantity, target: &str) -> Result<Vec<String>, String> {
+	// a single unit converts with its zero point, anything else like km/h is divided through
+	let tokens = lex(target)?;
+	let value = if let [Token::Identifier(name)] = tokens.as_slice()
+		&& let Some(unit) = find_unit(name, Some(quantity.dimension))
+		&& unit.dimension == quantity.dimension
+	{
+		unit.in_unit(quantity.value)
+	} else {
+		let unit = evaluate(target, None, Some(quantity.dimension))?;
+		if unit.dimension != quantity.dimension {
+			return Err(format!(
+				"cannot convert {} to {}",
+				dimension_name(quantity.dimension),
+				dimension_name(unit.dimension)
+			));
+		}
+
+		quantity.value / unit.value
+	};
+
+	let mut output = vec![format!("{} {}", format_number(value), target), format_number(value)];
+	if value != 0.0 && (value.abs() >= 1e6 || value.abs() < 1e-3) {
+		output.push(format!("{:e} {}", value, target));
+	}
+
+	output.dedup();
+	Ok(output)
+}
+
+// evaluates the input and returns the result in every format it can be copied as
+pub fn calculate(input: &str, ans: Option<f64>) -> Result<Vec<String>, String> {
+	let (expression, target) = split_conversion(input);
+	let target = if let Some(target) = target {
+		target
+	} else {
+		let quantity = evaluate(expression, ans, None)?;
+		return if quantity.dimension == DIMENSIONLESS {
+			Ok(vec![format_number(quantity.value)])
+		} else {
+			Ok(vec![format!(
+				"{} {}",
+				format_number(quantity.value),
+				format_base_unit(quantity.dimension)
+			)])
+		};
+	};
+
+	if let Some((radix, prefix)) = base_radix(target) {
+		let value = evaluate(expression, ans, None)?.require_number("changing base")?;
+		return format_radix(value, radix, prefix);
+	}
+
+	// ambiguous units in the expression are first read on their own, then in light of the target
+	let quantity = evaluate(expression, ans, None)?;
+	match convert(quantity, target) {
+		Ok(output) => Ok(output),
+		Err(error) => {
+			let target_dimension = evaluate(target, None, None).map(|unit| unit.dimension);
+			match target_dimension.and_then(|dimension| evaluate(expression, ans, Some(dimension))) {
+				Ok(quantity) => convert(quantity, target).map_err(|_| error),
+				Err(_) => Err(error),
+			}
+		}
+	}
+}
+
+pub fn format_number(number: f64) -> String {
+	if number.is_nan() {
+		String::from("NaN")
+	} else if number.is_infinite() {
+		String::from(if number < 0.0 { "-∞" } else { "∞" })
+	} else if number.fract() == 0.0 && number.abs() < 1e15 {
+		format!("{}", number as i64)
+	} else if number.abs() >= 1e15 || number.abs() < 1e-9 {
+		format!("{:e}", number)
+	} else {
+		// round away floating point noise like 0.1 + 0.2 = 0.30000000000000004
+		let formatted = format!("{:.12}", number);
+		formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+	}
+}
+
+// the last copied result is kept around so the next calculation can refer to it as ans
+pub fn read_ans() -> Option<f64> {
+	parse_ans(&std::fs::read_to_string(data_directory().join("calculator.ans")).ok()?)
+}
+
+// conversions are stored with their unit and base changes with their prefix, like 0xff
+fn parse_ans(contents: &str) -> Option<f64> {
+	match lex(contents.split_whitespace().next()?).ok()?.as_slice() {
+		[Token::Number(number)] => Some(*number),
+		[Token::Operator('-'), Token::Number(number)] => Some(-number),
+		_ => None,
+	}
+}
+
+pub fn write_ans(ans: &str) {
+	let path = data_directory().join("calculator.ans");
+	if let Err(error) = create_data_directory().and_then(|_| std::fs::write(&path, ans)) {
+		report(Error::Write(path, error));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn result(input: &str) -> Vec<String> {
+		calculate(input, None).unwrap()
+	}
+
+	#[test]
+	fn evaluates_expressions() {
+		assert_eq!(result("2^10*3"), ["3072"]);
+		assert_eq!(result("sqrt(2)"), ["1.414213562373"]);
+		assert_eq!(result("2 + 3 * 4"), ["14"]);
+		assert_eq!(result("2^3^2"), ["512"]);
+		assert_eq!(result("0.1 + 0.2"), ["0.3"]);
+	}
+
+	#[test]
+	fn reuses_the_previous_answer() {
+		assert_eq!(calculate("ans * 2", Some(21.0)).unwrap(), ["42"]);
+		assert_eq!(calculate("ans", None), Err(String::from("no previous answer")));
+	}
+
+	#[test]
+	fn changes_base() {
+		assert_eq!(result("0xff in bin"), ["0b11111111", "11111111"]);
+		assert_eq!(result("0b1010 in hex"), ["0xa", "a"]);
+		assert_eq!(result("-255 in hex"), ["-0xff", "-ff"]);
+		assert!(calculate("1.5 in hex", None).is_err());
+	}
+
+	#[test]
+	fn converts_units() {
+		assert_eq!(result("10 MiB in KB"), ["10485.76 KB", "10485.76"]);
+		assert_eq!(result("72F to C"), ["22.222222222222 C", "22.222222222222"]);
+		assert_eq!(result("3h 20m in s"), ["12000 s", "12000"]);
+		// m is read as minutes once the target says it has to be a time
+		assert_eq!(result("5 m in s"), ["300 s", "300"]);
+		assert_eq!(
+			calculate("5 km in s", None),
+			Err(String::from("cannot convert a length to a time"))
+		);
+	}
+
+	#[test]
+	fn large_factorials_are_infinite() {
+		assert_eq!(result("5!"), ["120"]);
+		assert_eq!(result("170!"), [format_number((1..=170).map(f64::from).product())]);
+		assert_eq!(result("171!"), ["∞"]);
+		assert_eq!(result("1e12!"), ["∞"]);
+	}
+
+	#[test]
+	fn rejects_unit_exponents_out_of_range() {
+		assert_eq!(result("m^2 * m^3"), ["1 m^5"]);
+		for input in ["m^200", "(m^2)^100", "m^99 * m^99", "1 / m^99 / m^99"] {
+			assert_eq!(calculate(input, None), Err(String::from("exponent too large")), "{}", input);
+		}
+	}
+
+	#[test]
+	fn remainder_needs_matching_units() {
+		assert_eq!(result("7 % 3"), ["1"]);
+		assert_eq!(result("10m % 3m"), ["1 m"]);
+		assert_eq!(
+			calculate("5m % 2s", None),
+			Err(String::from("cannot take the remainder of a length and a time"))
+		);
+	}
+
+	#[test]
+	fn reads_back_stored_answers() {
+		assert_eq!(parse_ans("42"), Some(42.0));
+		assert_eq!(parse_ans("-1.5"), Some(-1.5));
+		assert_eq!(parse_ans("1e20"), Some(1e20));
+		assert_eq!(parse_ans("12000 s"), Some(12000.0));
+		assert_eq!(parse_ans("0xff"), Some(255.0));
+		assert_eq!(parse_ans("-0x1f"), Some(-31.0));
+		assert_eq!(parse_ans("0b1010"), Some(10.0));
+		assert_eq!(parse_ans(""), None);
+		assert_eq!(parse_ans("hello"), None);
+		assert_eq!(calculate("ans + 1", parse_ans("0xff")).unwrap(), ["256"]);
+	}
+}
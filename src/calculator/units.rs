@@ -0,0 +1,181 @@
+// exponents of length, mass, time, data and temperature
+pub type Dimension = [i8; 5];
+
+pub const DIMENSIONLESS: Dimension = [0, 0, 0, 0, 0];
+const LENGTH: Dimension = [1, 0, 0, 0, 0];
+const MASS: Dimension = [0, 1, 0, 0, 0];
+const TIME: Dimension = [0, 0, 1, 0, 0];
+const DATA: Dimension = [0, 0, 0, 1, 0];
+const TEMPERATURE: Dimension = [0, 0, 0, 0, 1];
+const SPEED: Dimension = [1, 0, -1, 0, 0];
+
+const BASE_UNIT_NAMES: [&str; 5] = ["m", "kg", "s", "B", "K"];
+
+pub struct Unit {
+	pub names: &'static [&'static str],
+	pub scale: f64,  // size of the unit in base units
+	pub offset: f64, // only temperatures have a zero point that differs from the base unit
+	pub dimension: Dimension,
+}
+
+impl Unit {
+	pub fn to_base(&self, value: f64) -> f64 {
+		value * self.scale + self.offset
+	}
+
+	pub fn in_unit(&self, value: f64) -> f64 {
+		(value - self.offset) / self.scale
+	}
+}
+
+const fn unit(names: &'static [&'static str], scale: f64, dimension: Dimension) -> Unit {
+	Unit {
+		names,
+		scale,
+		offset: 0.0,
+		dimension,
+	}
+}
+
+// earlier entries win when a name is ambiguous and nothing else decides, e.g. m is meters unless
+// it is next to another time
+pub static UNITS: &[Unit] = &[
+	// length
+	unit(&["m", "meter", "meters", "metre", "metres"], 1.0, LENGTH),
+	unit(&["km", "kilometer", "kilometers"], 1e3, LENGTH),
+	unit(&["cm", "centimeter", "centimeters"], 1e-2, LENGTH),
+	unit(&["mm", "millimeter", "millimeters"], 1e-3, LENGTH),
+	unit(&["um", "µm", "micrometer", "micrometers"], 1e-6, LENGTH),
+	unit(&["nm", "nanometer", "nanometers"], 1e-9, LENGTH),
+	unit(&["in", "inch", "inches"], 0.0254, LENGTH),
+	unit(&["ft", "foot", "feet"], 0.3048, LENGTH),
+	unit(&["yd", "yard", "yards"], 0.9144, LENGTH),
+	unit(&["mi", "mile", "miles"], 1609.344, LENGTH),
+	unit(&["nmi"], 1852.0, LENGTH),
+	// mass
+	unit(&["kg", "kilogram", "kilograms"], 1.0, MASS),
+	unit(&["g", "gram", "grams"], 1e-3, MASS),
+	unit(&["mg", "milligram", "milligrams"], 1e-6, MASS),
+	unit(&["t", "tonne", "tonnes"], 1e3, MASS),
+	unit(&["lb", "lbs", "pound", "pounds"], 0.453_592_37, MASS),
+	unit(&["oz", "ounce", "ounces"], 0.028_349_523_125, MASS),
+	unit(&["st", "stone"], 6.350_293_18, MASS),
+	// time
+	unit(&["s", "sec", "second", "seconds"], 1.0, TIME),
+	unit(&["ms", "millisecond", "milliseconds"], 1e-3, TIME),
+	unit(&["us", "µs", "microsecond", "microseconds"], 1e-6, TIME),
+	unit(&["ns", "nanosecond", "nanoseconds"], 1e-9, TIME),
+	unit(&["min", "m", "minute", "minutes"], 60.0, TIME),
+	unit(&["h", "hr", "hour", "hours"], 3600.0, TIME),
+	unit(&["d", "day", "days"], 86400.0, TIME),
+	unit(&["wk", "week", "weeks"], 604800.0, TIME),
+	unit(&["mo", "month", "months"], 2629800.0, TIME),
+	unit(&["y", "yr", "year", "years"], 31557600.0, TIME),
+	// data, bytes come before bits so a lowercase mb still means megabytes
+	unit(&["B", "byte", "bytes"], 1.0, DATA),
+	unit(&["kB", "KB", "kilobyte", "kilobytes"], 1e3, DATA),
+	unit(&["MB", "megabyte", "megabytes"], 1e6, DATA),
+	unit(&["GB", "gigabyte", "gigabytes"], 1e9, DATA),
+	unit(&["TB", "terabyte", "terabytes"], 1e12, DATA),
+	unit(&["PB", "petabyte", "petabytes"], 1e15, DATA),
+	unit(&["KiB", "kibibyte", "kibibytes"], 1024.0, DATA),
+	unit(&["MiB", "mebibyte", "mebibytes"], 1048576.0, DATA),
+	unit(&["GiB", "gibibyte", "gibibytes"], 1073741824.0, DATA),
+	unit(&["TiB", "tebibyte", "tebibytes"], 1099511627776.0, DATA),
+	unit(&["PiB", "pebibyte", "pebibytes"], 1125899906842624.0, DATA),
+	unit(&["b", "bit", "bits"], 0.125, DATA),
+	unit(&["Kb", "kbit", "kilobit", "kilobits"], 125.0, DATA),
+	unit(&["Mb", "Mbit", "megabit", "megabits"], 1.25e5, DATA),
+	unit(&["Gb", "Gbit", "gigabit", "gigabits"], 1.25e8, DATA),
+	unit(&["Kib", "kibit", "kibibit", "kibibits"], 128.0, DATA),
+	unit(&["Mib", "mibit", "mebibit", "mebibits"], 131072.0, DATA),
+	// temperature
+	unit(&["K", "kelvin"], 1.0, TEMPERATURE),
+	Unit {
+		names: &["C", "°C", "celsius"],
+		scale: 1.0,
+		offset: 273.15,
+		dimension: TEMPERATURE,
+	},
+	Unit {
+		names: &["F", "°F", "fahrenheit"],
+		scale: 5.0 / 9.0,
+		offset: 273.15 - 32.0 * 5.0 / 9.0,
+		dimension: TEMPERATURE,
+	},
+	// speed
+	unit(&["kph", "kmh"], 1.0 / 3.6, SPEED),
+	unit(&["mph"], 0.44704, SPEED),
+	unit(&["kn", "knot", "knots"], 0.514_444_444_444, SPEED),
+];
+
+// exact names are preferred over a case insensitive match, and the preferred dimension decides
+// between units that share a name
+pub fn find_unit(name: &str, preferred: Option<Dimension>) -> Option<&'static Unit> {
+	let mut candidates = UNITS
+		.iter()
+		.filter(|unit| unit.names.contains(&name))
+		.collect::<Vec<&Unit>>();
+
+	if candidates.is_empty() {
+		candidates = UNITS
+			.iter()
+			.filter(|unit| {
+				unit.names
+					.iter()
+					.any(|unit_name| unit_name.eq_ignore_ascii_case(name))
+			})
+			.collect();
+	}
+
+	preferred
+		.and_then(|dimension| candidates.iter().find(|unit| unit.dimension == dimension))
+		.or(candidates.first())
+		.copied()
+}
+
+pub fn dimension_name(dimension: Dimension) -> String {
+	match dimension {
+		DIMENSIONLESS => String::from("a number"),
+		LENGTH => String::from("a length"),
+		MASS => String::from("a mass"),
+		TIME => String::from("a time"),
+		DATA => String::from("a data size"),
+		TEMPERATURE => String::from("a temperature"),
+		SPEED => String::from("a speed"),
+		_ => format_base_unit(dimension),
+	}
+}
+
+// writes a dimension out in base units, e.g. m/s or kg*m^2
+pub fn format_base_unit(dimension: Dimension) -> String {
+	// widened, negating -128 does not fit in an i8
+	let format_part = |index: usize, exponent: i16| {
+		if exponent == 1 {
+			BASE_UNIT_NAMES[index].to_string()
+		} else {
+			format!("{}^{}", BASE_UNIT_NAMES[index], exponent)
+		}
+	};
+
+	let numerator = (0..5)
+		.filter(|&index| dimension[index] > 0)
+		.map(|index| format_part(index, dimension[index] as i16))
+		.collect::<Vec<String>>();
+
+	let denominator = (0..5)
+		.filter(|&index| dimension[index] < 0)
+		.map(|index| format_part(index, -(dimension[index] as i16)))
+		.collect::<Vec<String>>();
+
+	let numerator = if numerator.is_empty() {
+		String::from("1")
+	} else {
+		numerator.join("*")
+	};
+	if denominator.is_empty() {
+		numerator
+	} else {
+		format!("{}/{}", numerator, denominator.join("/"))
+	}
+}
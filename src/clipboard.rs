@@ -0,0 +1,29 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// the copying program keeps serving the clipboard after we exit, which a wayland client that owns
+// the selection itself could not do
+fn clipboard_command() -> Command {
+	if std::env::var("WAYLAND_DISPLAY").is_ok() {
+		Command::new("wl-copy")
+	} else {
+		let mut command = Command::new("xclip");
+		command.args(["-selection", "clipboard"]);
+		command
+	}
+}
+
+pub fn copy(text: &str) -> std::io::Result<()> {
+	let mut child = clipboard_command().stdin(Stdio::piped()).spawn()?;
+	child.stdin.take().unwrap().write_all(text.as_bytes())?;
+
+	let status = child.wait()?;
+	if status.success() {
+		Ok(())
+	} else {
+		Err(std::io::Error::other(format!(
+			"clipboard program exited with {}",
+			status
+		)))
+	}
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{OpenOptions, Permissions};
+use std::io::{Read, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::os::unix::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::config::{
+	create_data_directory, data_directory, home_directory, ClipboardSource, CONFIG,
+};
+use crate::error::{report, Error};
+
+// longest preview shown in the list, in characters
+const PREVIEW_LENGTH: usize = 60;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct StoredEntry {
+	timestamp: u64, // milliseconds, doubles as the id of the entry
+	text: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Entry {
+	pub id: String,
+	pub preview: String,
+	pub timestamp: Option<u64>, // seconds, cliphist does not tell us when something was copied
+	pub lines: usize,
+}
+
+fn history_path() -> PathBuf {
+	data_directory().join("clipboard_history.jsonl")
+}
+
+fn cliphist_database() -> PathBuf {
+	if let Ok(directory) = std::env::var("XDG_CACHE_HOME") {
+		PathBuf::from(directory).join("cliphist/db")
+	} else {
+		home_directory().join(".cache/cliphist/db")
+	}
+}
+
+// cliphist already keeps a history, so there is no point in keeping a second one next to it
+fn use_cliphist() -> bool {
+	match CONFIG.clipboard.source {
+		ClipboardSource::Auto => cliphist_database().exists(),
+		ClipboardSource::Builtin => false,
+		ClipboardSource::Cliphist => true,
+	}
+}
+
+pub fn preview(text: &str) -> String {
+	let line = text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+	if line.chars().count() > PREVIEW_LENGTH {
+		format!("{}…", line.chars().take(PREVIEW_LENGTH).collect::<String>())
+	} else if text.trim().lines().count() > 1 {
+		format!("{} …", line)
+	} else {
+		line.to_string()
+	}
+}
+
+// newest first
+fn read_history() -> Vec<StoredEntry> {
+	let contents = match std::fs::read_to_string(history_path()) {
+		Ok(contents) => contents,
+		Err(_) => return Vec::new(), // nothing copied yet
+	};
+
+	contents
+		.lines()
+		.filter_map(|line| serde_json::from_str(line).ok())
+		.collect()
+}
+
+fn write_history(entries: &[StoredEntry]) {
+	let contents = entries
+		.iter()
+		.filter_map(|entry| serde_json::to_string(entry).ok())
+		.map(|line| line + "\n")
+		.collect::<String>();
+
+	// copied passwords end up in here too, so only the user may read it. files written before
+	// that was the case are tightened as well
+	if let Err(error) = create_data_directory()
+		.and_then(|_| {
+			OpenOptions::new()
+				.write(true)
+				.create(true)
+				.truncate(true)
+				.mode(0o600)
+				.open(history_path())
+		})
+		.and_then(|mut file| {
+			file.set_permissions(Permissions::from_mode(0o600))?;
+			file.write_all(contents.as_bytes())
+		})
+	{
+		report(Error::Write(history_path(), error));
+	}
+}
+
+fn run_cliphist(args: &[&str], input: &str) -> Option<String> {
+	let mut child = Command::new("cliphist")
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.spawn()
+		.ok()?;
+
+	child.stdin.take()?.write_all(input.as_bytes()).ok()?;
+	let output = child.wait_with_output().ok()?;
+	if output.status.success() {
+		String::from_utf8(output.stdout).ok()
+	} else {
+		None
+	}
+}
+
+pub fn get_entries() -> Vec<Entry> {
+	if use_cliphist() {
+		// each line is the id and a preview separated by a tab, the whole line is what decode wants
+		run_cliphist(&["list"], "")
+			.unwrap_or_default()
+			.lines()
+			.filter(|line| !line.contains("[[ binary data"))
+			.map(|line| Entry {
+				id: line.to_string(),
+				preview: preview(line.split_once('\t').map(|(_, text)| text).unwrap_or(line)),
+				timestamp: None,
+				lines: 1,
+			})
+			.collect()
+	} else {
+		read_history()
+			.into_iter()
+			.map(|entry| Entry {
+				id: entry.timestamp.to_string(),
+				preview: preview(&entry.text),
+				timestamp: Some(entry.timestamp / 1000),
+				lines: entry.text.trim().lines().count(),
+			})
+			.collect()
+	}
+}
+
+pub fn entry_text(id: &str) -> Option<String> {
+	if use_cliphist() {
+		run_cliphist(&["decode"], id)
+	} else {
+		read_history()
+			.into_iter()
+			.find(|entry| entry.timestamp.to_string() == id)
+			.map(|entry| entry.text)
+	}
+}
+
+pub fn delete_entry(id: &str) {
+	if use_cliphist() {
+		if run_cliphist(&["delete"], id).is_none() {
+			report(Error::Launch(
+				String::from("cliphist delete"),
+				std::io::Error::other("the entry was not deleted"),
+			));
+		}
+	} else {
+		let mut history = read_history();
+		history.retain(|entry| entry.timestamp.to_string() != id);
+		write_history(&history);
+	}
+}
+
+fn store(text: String) {
+	let config = &CONFIG.clipboard;
+	if text.trim().is_empty() || text.len() > config.max_entry_size {
+		return;
+	}
+
+	// copying something again moves it back to the top instead of listing it twice
+	let mut history = read_history();
+	history.retain(|entry| entry.text != text);
+	history.insert(
+		0,
+		StoredEntry {
+			timestamp: chrono::Local::now().timestamp_millis() as u64,
+			text,
+		},
+	);
+
+	history.truncate(config.max_entries);
+	write_history(&history);
+}
+
+// run by wl-paste for every clipboard change with the new contents on stdin
+pub fn store_from_stdin() {
+	// wl-paste marks contents offered with a password manager hint as sensitive, those never get
+	// written to disk
+	if std::env::var("CLIPBOARD_STATE").is_ok_and(|state| state != "data") {
+		return;
+	}
+
+	let mut text = String::new();
+	if std::io::stdin().read_to_string(&mut text).is_ok() {
+		store(text);
+	}
+}
+
+// replaces this process with wl-paste, which calls us back with --store-clipboard on every change
+pub fn watch() {
+	let executable = std::env::current_exe().unwrap_or(PathBuf::from("bansheefinder3"));
+	let error = Command::new("wl-paste")
+		.args(["--type", "text", "--watch"])
+		.arg(executable)
+		.arg("--store-clipboard")
+		.exec();
+
+	eprintln!("Could not watch the clipboard {:?}", error);
+	std::process::exit(1);
+}
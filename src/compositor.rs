@@ -0,0 +1,336 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::process::Command;
+
+#[derive(Clone, Debug)]
+pub struct Window {
+	pub id: String,
+	pub title: String,
+	pub app_id: String,
+	pub workspace: Option<String>,
+}
+
+pub trait Compositor {
+	fn windows(&self) -> Option<Vec<Window>>;
+	fn focus(&self, id: &str) -> std::io::Result<()>;
+}
+
+// picks the compositor we are running under from the environment it sets up for its clients
+pub fn detect() -> Option<Box<dyn Compositor>> {
+	if std::env::var("NIRI_SOCKET").is_ok() {
+		Some(Box::new(Niri))
+	} else if std::env::var("SWAYSOCK").is_ok() {
+		Some(Box::new(Sway))
+	} else if std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok() {
+		Some(Box::new(Hyprland))
+	} else {
+		None
+	}
+}
+
+pub fn get_windows() -> Vec<Window> {
+	detect()
+		.and_then(|compositor| compositor.windows())
+		.unwrap_or_default()
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+	let output = Command::new(program).args(args).output().ok()?;
+	if output.status.success() {
+		String::from_utf8(output.stdout).ok()
+	} else {
+		None
+	}
+}
+
+fn run_status(program: &str, args: &[&str]) -> std::io::Result<()> {
+	let status = Command::new(program).args(args).status()?;
+	if status.success() {
+		Ok(())
+	} else {
+		Err(std::io::Error::other(format!(
+			"{} exited with {}",
+			program, status
+		)))
+	}
+}
+
+pub struct Niri;
+
+#[derive(Deserialize)]
+struct NiriWindow {
+	id: u64,
+	title: Option<String>,
+	app_id: Option<String>,
+	workspace_id: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct NiriWorkspace {
+	id: u64,
+	idx: u64,
+	name: Option<String>,
+}
+
+pub fn parse_niri_windows(windows: &str, workspaces: &str) -> Option<Vec<Window>> {
+	let windows: Vec<NiriWindow> = serde_json::from_str(windows).ok()?;
+	let workspaces: Vec<NiriWorkspace> = serde_json::from_str(workspaces).unwrap_or_default();
+
+	// niri refers to workspaces by an internal id, show the name or the index people see instead
+	let workspace_names = workspaces
+		.into_iter()
+		.map(|workspace| {
+			(
+				workspace.id,
+				workspace.name.unwrap_or(workspace.idx.to_string()),
+			)
+		})
+		.collect::<HashMap<u64, String>>();
+
+	Some(
+		windows
+			.into_iter()
+			.map(|window| Window {
+				id: window.id.to_string(),
+				title: window.title.unwrap_or_default(),
+				app_id: window.app_id.unwrap_or_default(),
+				workspace: window
+					.workspace_id
+					.and_then(|id| workspace_names.get(&id).cloned()),
+			})
+			.collect(),
+	)
+}
+
+impl Compositor for Niri {
+	fn windows(&self) -> Option<Vec<Window>> {
+		let windows = run("niri", &["msg", "--json", "windows"])?;
+		let workspaces = run("niri", &["msg", "--json", "workspaces"]).unwrap_or_default();
+		parse_niri_windows(&windows, &workspaces)
+	}
+
+	fn focus(&self, id: &str) -> std::io::Result<()> {
+		run_status("niri", &["msg", "action", "focus-window", "--id", id])
+	}
+}
+
+pub struct Sway;
+
+#[derive(Deserialize)]
+struct SwayWindowProperties {
+	class: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SwayNode {
+	id: u64,
+	name: Option<String>,
+	#[serde(rename = "type")]
+	node_type: String,
+	pid: Option<u32>,
+	app_id: Option<String>,
+	window_properties: Option<SwayWindowProperties>,
+	#[serde(default)]
+	nodes: Vec<SwayNode>,
+	#[serde(default)]
+	floating_nodes: Vec<SwayNode>,
+}
+
+fn collect_sway_windows(node: SwayNode, workspace: Option<&String>, output: &mut Vec<Window>) {
+	let workspace = if node.node_type == "workspace" {
+		node.name.as_ref()
+	} else {
+		workspace
+	};
+
+	// the scratchpad is a hidden workspace, windows in it cannot be switched to directly
+	if workspace.is_some_and(|workspace| workspace == "__i3_scratch") {
+		return;
+	}
+
+	if node.pid.is_some() && node.nodes.is_empty() {
+		let app_id = node.app_id.clone().or(node
+			.window_properties
+			.as_ref()
+			.and_then(|properties| properties.class.clone()));
+
+		output.push(Window {
+			id: node.id.to_string(),
+			title: node.name.clone().unwrap_or_default(),
+			app_id: app_id.unwrap_or_default(),
+			workspace: workspace.cloned(),
+		});
+	}
+
+	let workspace = workspace.cloned();
+	for child in node.nodes.into_iter().chain(node.floating_nodes) {
+		collect_sway_windows(child, workspace.as_ref(), output);
+	}
+}
+
+pub fn parse_sway_tree(tree: &str) -> Option<Vec<Window>> {
+	let root: SwayNode = serde_json::from_str(tree).ok()?;
+	let mut output = Vec::new();
+	collect_sway_windows(root, None, &mut output);
+	Some(output)
+}
+
+impl Compositor for Sway {
+	fn windows(&self) -> Option<Vec<Window>> {
+		parse_sway_tree(&run("swaymsg", &["-r", "-t", "get_tree"])?)
+	}
+
+	fn focus(&self, id: &str) -> std::io::Result<()> {
+		run_status("swaymsg", &[&format!("[con_id={}]", id), "focus"])
+	}
+}
+
+pub struct Hyprland;
+
+#[derive(Deserialize)]
+struct HyprlandWorkspace {
+	name: String,
+}
+
+#[derive(Deserialize)]
+struct HyprlandClient {
+	address: String,
+	title: String,
+	class: String,
+	workspace: HyprlandWorkspace,
+	#[serde(default = "default_mapped")]
+	mapped: bool,
+}
+
+fn default_mapped() -> bool {
+	true
+}
+
+pub fn parse_hyprland_clients(clients: &str) -> Option<Vec<Window>> {
+	let clients: Vec<HyprlandClient> = serde_json::from_str(clients).ok()?;
+	Some(
+		clients
+			.into_iter()
+			.filter(|client| client.mapped)
+			.map(|client| Window {
+				id: client.address,
+				title: client.title,
+				app_id: client.class,
+				workspace: Some(client.workspace.name),
+			})
+			.collect(),
+	)
+}
+
+impl Compositor for Hyprland {
+	fn windows(&self) -> Option<Vec<Window>> {
+		parse_hyprland_clients(&run("hyprctl", &["clients", "-j"])?)
+	}
+
+	fn focus(&self, id: &str) -> std::io::Result<()> {
+		run_status(
+			"hyprctl",
+			&["dispatch", "focuswindow", &format!("address:{}", id)],
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// (id, title, app_id, workspace) so whole lists can be compared at once
+	fn summarize(windows: Vec<Window>) -> Vec<(String, String, String, Option<String>)> {
+		windows
+			.into_iter()
+			.map(|window| (window.id, window.title, window.app_id, window.workspace))
+			.collect()
+	}
+
+	fn entry(
+		id: &str,
+		title: &str,
+		app_id: &str,
+		workspace: Option<&str>,
+	) -> (String, String, String, Option<String>) {
+		(
+			id.to_string(),
+			title.to_string(),
+			app_id.to_string(),
+			workspace.map(str::to_string),
+		)
+	}
+
+	#[test]
+	fn parses_niri_windows() {
+		let windows = parse_niri_windows(
+			include_str!("../tests/fixtures/niri_windows.json"),
+			include_str!("../tests/fixtures/niri_workspaces.json"),
+		)
+		.unwrap();
+
+		assert_eq!(
+			summarize(windows),
+			vec![
+				entry("12", "~/code/bansheefinder3", "Alacritty", Some("code")),
+				entry("7", "Inbox - Mozilla Firefox", "firefox", Some("2")),
+				entry("19", "", "", None),
+			]
+		);
+	}
+
+	#[test]
+	fn parses_niri_windows_without_workspaces() {
+		let windows =
+			parse_niri_windows(include_str!("../tests/fixtures/niri_windows.json"), "").unwrap();
+
+		assert!(windows.iter().all(|window| window.workspace.is_none()));
+	}
+
+	#[test]
+	fn parses_sway_tree() {
+		let windows = parse_sway_tree(include_str!("../tests/fixtures/sway_tree.json")).unwrap();
+
+		// the tabbed container is not a window and the scratchpad is left out
+		assert_eq!(
+			summarize(windows),
+			vec![
+				entry("5", "~/code", "foot", Some("1")),
+				entry("7", "GIMP", "Gimp-2.10", Some("1")),
+				entry("8", "Picture-in-Picture", "firefox", Some("1")),
+			]
+		);
+	}
+
+	#[test]
+	fn parses_hyprland_clients() {
+		let windows =
+			parse_hyprland_clients(include_str!("../tests/fixtures/hyprland_clients.json"))
+				.unwrap();
+
+		assert_eq!(
+			summarize(windows),
+			vec![
+				entry(
+					"0x5d1b4a2c8e40",
+					"nvim ~/.config/hypr/hyprland.conf",
+					"kitty",
+					Some("1")
+				),
+				entry(
+					"0x5d1b4a31f7a0",
+					"Hyprland Wiki - Mozilla Firefox",
+					"firefox",
+					Some("web")
+				),
+			]
+		);
+	}
+
+	#[test]
+	fn rejects_invalid_output() {
+		assert!(parse_niri_windows("niri is not running", "").is_none());
+		assert!(parse_sway_tree("").is_none());
+		assert!(parse_hyprland_clients("{}").is_none());
+	}
+}
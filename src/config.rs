@@ -0,0 +1,545 @@
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::autocomplete::{ProjectAction, SessionAction, UnitAction};
+use crate::error::{report, Error};
+use crate::keymap::Action;
+
+lazy_static! {
+	pub static ref CONFIG: Config = read_config();
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+	pub clipboard: ClipboardConfig,
+	pub commands: Vec<CustomCommand>,
+	pub confirmation: ConfirmationConfig,
+	pub font: FontConfig,
+	pub icons: IconsConfig,
+	pub keybindings: KeybindingsConfig,
+	pub layer_shell: LayerShellConfig,
+	pub projects: ProjectsConfig,
+	pub session: SessionConfig,
+	pub shell: ShellConfig,
+	pub symbols: SymbolsConfig,
+	pub systemd: SystemdConfig,
+	pub theme: ThemeConfig,
+	pub window: WindowConfig,
+}
+
+// an entry of its own in the default list, e.g.
+// [[commands]]
+// label = "restart"
+// command = "systemctl reboot"
+// frecency = false
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct CustomCommand {
+	pub label: String,
+	pub description: Option<String>,
+	pub icon: Option<String>, // name of a material icon
+	pub command: String,      // run with sh -c
+	pub cwd: Option<String>,
+	pub env: HashMap<String, String>,
+	pub confirm: bool,
+	pub frecency: bool, // whether launching it counts towards its ranking
+}
+
+impl Default for CustomCommand {
+	fn default() -> Self {
+		CustomCommand {
+			label: String::new(),
+			description: None,
+			icon: None,
+			command: String::new(),
+			cwd: None,
+			env: HashMap::new(),
+			confirm: false,
+			frecency: true,
+		}
+	}
+}
+
+// killall and sudo are always confirmed, these programs are confirmed on top of them
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ConfirmationConfig {
+	pub commands: Vec<String>,
+	pub require_typing: bool, // type a word instead of pressing enter a second time
+}
+
+impl Default for ConfirmationConfig {
+	fn default() -> Self {
+		ConfirmationConfig {
+			commands: [
+				"restart",
+				"off",
+				"hibernate",
+				"rest",
+				"reboot",
+				"poweroff",
+				"shutdown",
+				"halt",
+			]
+			.iter()
+			.map(|command| command.to_string())
+			.collect(),
+			require_typing: false,
+		}
+	}
+}
+
+pub fn find_command(label: &str) -> Option<&'static CustomCommand> {
+	CONFIG
+		.commands
+		.iter()
+		.find(|command| command.label == label)
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardSource {
+	#[default]
+	Auto, // cliphist if it has a database, our own history otherwise
+	Builtin,
+	Cliphist,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+	pub source: ClipboardSource,
+	pub max_entries: usize,
+	pub max_entry_size: usize, // in bytes, anything larger is not kept
+}
+
+impl Default for ClipboardConfig {
+	fn default() -> Self {
+		ClipboardConfig {
+			source: ClipboardSource::Auto,
+			max_entries: 200,
+			max_entry_size: 64 * 1024,
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ProjectsConfig {
+	pub roots: Vec<String>,
+	pub max_depth: usize,
+	pub markers: Vec<String>, // a directory containing any of these is a project
+	pub ignored: Vec<String>, // directory names that are never descended into
+	pub boost_by_commit_time: bool,
+	pub actions: ProjectActionsConfig,
+}
+
+impl Default for ProjectsConfig {
+	fn default() -> Self {
+		ProjectsConfig {
+			roots: vec![String::from("~/Projects")],
+			max_depth: 3,
+			markers: vec![String::from(".git")],
+			ignored: vec![
+				String::from("node_modules"),
+				String::from("target"),
+				String::from("build"),
+				String::from("dist"),
+				String::from("vendor"),
+			],
+			boost_by_commit_time: true,
+			actions: ProjectActionsConfig::default(),
+		}
+	}
+}
+
+// commands for the session mode, the defaults work on any systemd machine
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+	pub lock: String,
+	pub suspend: String,
+	pub hibernate: String,
+	pub reboot: String,
+	pub poweroff: String,
+	pub logout: String,
+}
+
+impl Default for SessionConfig {
+	fn default() -> Self {
+		SessionConfig {
+			lock: String::from("loginctl lock-session"),
+			suspend: String::from("systemctl suspend"),
+			hibernate: String::from("systemctl hibernate"),
+			reboot: String::from("systemctl reboot"),
+			poweroff: String::from("systemctl poweroff"),
+			logout: String::from("loginctl terminate-session \"$XDG_SESSION_ID\""),
+		}
+	}
+}
+
+impl SessionConfig {
+	pub fn command(&self, action: SessionAction) -> String {
+		match action {
+			SessionAction::Lock => self.lock.clone(),
+			SessionAction::Suspend => self.suspend.clone(),
+			SessionAction::Hibernate => self.hibernate.clone(),
+			SessionAction::Reboot => self.reboot.clone(),
+			SessionAction::Poweroff => self.poweroff.clone(),
+			SessionAction::Logout => self.logout.clone(),
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+	pub width: f32,
+	pub height: f32, // the most the window grows to when auto_fit is on
+	pub scale: f32,
+	pub max_rows: usize, // rows shown before the list scrolls when auto_fit is on
+	pub padding: u16,    // between the border and the content
+	pub antialiasing: bool,
+	pub auto_fit: bool, // shrink the window to the rows there are
+}
+
+impl Default for WindowConfig {
+	fn default() -> Self {
+		WindowConfig {
+			width: 300.0,
+			height: 200.0,
+			scale: 1.5,
+			max_rows: 20,
+			padding: 0,
+			antialiasing: false,
+			auto_fit: false,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Edge {
+	Top,
+	Right,
+	Bottom,
+	Left,
+}
+
+// an overlay above every window instead of a regular window, on compositors with wlr-layer-shell,
+// e.g.
+// [layer_shell]
+// enabled = true
+// anchor = ["top"]
+// margin = [200, 0, 0, 0]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LayerShellConfig {
+	pub enabled: bool,
+	pub anchor: Vec<Edge>, // the edges of the screen it sticks to, centered when there are none
+	pub margin: [i32; 4],  // top, right, bottom, left
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FontWeight {
+	Thin,
+	ExtraLight,
+	Light,
+	#[serde(alias = "normal")]
+	Regular,
+	#[default]
+	Medium,
+	SemiBold,
+	Bold,
+	ExtraBold,
+	Black,
+}
+
+// any weight of Noto Sans works without it being installed, other families have to be
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct FontConfig {
+	pub family: String,
+	pub weight: FontWeight,
+	pub italic: bool,
+	pub size: f32, // of the list entries, everything else is sized relative to it
+}
+
+impl Default for FontConfig {
+	fn default() -> Self {
+		FontConfig {
+			family: String::from("Noto Sans"),
+			weight: FontWeight::Medium,
+			italic: false,
+			size: 9.0,
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct IconsConfig {
+	pub enabled: bool,
+	pub theme: Option<String>, // defaults to the gtk icon theme
+	pub size: u16,
+	pub cache_seconds: u64, // how long resolved icon paths are reused before the theme is searched again
+}
+
+impl Default for IconsConfig {
+	fn default() -> Self {
+		IconsConfig {
+			enabled: true,
+			theme: None,
+			size: 16,
+			cache_seconds: 60 * 60 * 24,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeymapPreset {
+	#[default]
+	Default,
+	Emacs, // ctrl+n and ctrl+p move the selection, ctrl+g cancels
+	Vim,   // ctrl+j and ctrl+k move the selection, ctrl+d and ctrl+u page
+}
+
+// bindings are added on top of the preset, e.g.
+// [keybindings]
+// preset = "emacs"
+// bindings = { "ctrl+t" = "run-in-terminal", "alt+enter" = "accept-alt" }
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct KeybindingsConfig {
+	pub preset: KeymapPreset,
+	pub bindings: HashMap<String, Action>,
+}
+
+// name is one of purple, light, high_contrast or a custom theme, e.g.
+// [theme]
+// name = "nord"
+// [theme.custom.nord]
+// base = "purple"
+// background = "#2e3440"
+// selected_row = "#4c566a"
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+	pub name: String,
+	pub follow_system: bool, // use the light theme while the desktop prefers light colors
+	pub light: String,
+	pub custom: HashMap<String, toml::Table>,
+}
+
+impl Default for ThemeConfig {
+	fn default() -> Self {
+		ThemeConfig {
+			name: String::from("purple"),
+			follow_system: false,
+			light: String::from("light"),
+			custom: HashMap::new(),
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ShellConfig {
+	pub import_aliases: bool,
+	pub shell: Option<String>,           // defaults to $SHELL
+	pub alias_file: Option<String>,      // read aliases from this file instead of asking the shell
+	pub cache_seconds: u64,              // how long aliases read from the shell are reused
+	pub script_directories: Vec<String>, // searched even when they are not on $PATH
+	pub terminal: String,                // commands run in a terminal are appended to this
+}
+
+impl Default for ShellConfig {
+	fn default() -> Self {
+		ShellConfig {
+			import_aliases: true,
+			shell: None,
+			alias_file: None,
+			cache_seconds: 60 * 60,
+			script_directories: vec![String::from("~/bin"), String::from("~/.local/bin")],
+			terminal: default_terminal(),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolAction {
+	#[default]
+	Copy,
+	Type,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SymbolsConfig {
+	pub action: SymbolAction,
+	pub type_command: String, // {text} is replaced with the quoted symbol
+}
+
+impl Default for SymbolsConfig {
+	fn default() -> Self {
+		let type_command = if std::env::var("WAYLAND_DISPLAY").is_ok() {
+			"wtype -- {text}"
+		} else {
+			"xdotool type -- {text}"
+		};
+
+		SymbolsConfig {
+			action: SymbolAction::Copy,
+			type_command: String::from(type_command),
+		}
+	}
+}
+
+// command templates for project actions, {path} is replaced with the quoted project path. commands
+// are run from inside the project directory
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ProjectActionsConfig {
+	pub default: ProjectAction,
+	pub editor: String,
+	pub terminal: String,
+	pub file_manager: String,
+	pub git_status: String,
+}
+
+impl Default for ProjectActionsConfig {
+	fn default() -> Self {
+		ProjectActionsConfig {
+			default: ProjectAction::Editor,
+			editor: String::from("code {path}"),
+			terminal: terminal_program(),
+			file_manager: String::from("xdg-open {path}"),
+			// the shell keeps the terminal open once git is done
+			git_status: format!(
+				"{} sh -c 'git status; exec \"${{SHELL:-sh}}\"'",
+				default_terminal()
+			),
+		}
+	}
+}
+
+impl ProjectActionsConfig {
+	pub fn template(&self, action: ProjectAction) -> &String {
+		match action {
+			ProjectAction::Editor => &self.editor,
+			ProjectAction::Terminal => &self.terminal,
+			ProjectAction::FileManager => &self.file_manager,
+			ProjectAction::GitStatus => &self.git_status,
+		}
+	}
+}
+
+// the systemd units mode, status and journal open in the terminal command with the systemctl or
+// journalctl invocation appended
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SystemdConfig {
+	pub default: UnitAction,
+	pub system_units: bool, // list system units next to the user's own
+	pub unit_types: Vec<String>,
+	pub systemctl: String,
+	pub journalctl: String,
+	pub terminal: String,
+}
+
+impl Default for SystemdConfig {
+	fn default() -> Self {
+		SystemdConfig {
+			default: UnitAction::Status,
+			system_units: true,
+			unit_types: vec![String::from("service"), String::from("timer"), String::from("socket")],
+			systemctl: String::from("systemctl"),
+			journalctl: String::from("journalctl"),
+			terminal: default_terminal(),
+		}
+	}
+}
+
+// Debian's alternative is there when $TERMINAL is not set
+fn terminal_program() -> String {
+	std::env::var("TERMINAL")
+		.ok()
+		.filter(|terminal| !terminal.is_empty())
+		.unwrap_or_else(|| String::from("x-terminal-emulator"))
+}
+
+// $TERMINAL only names the program, commands follow -e
+fn default_terminal() -> String {
+	format!("{} -e", terminal_program())
+}
+
+pub fn home_directory() -> PathBuf {
+	PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| String::from("/")))
+}
+
+// expands a leading ~ so paths in the config file can be written relative to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+	if path == "~" {
+		home_directory()
+	} else if let Some(rest) = path.strip_prefix("~/") {
+		home_directory().join(rest)
+	} else {
+		PathBuf::from(path)
+	}
+}
+
+pub fn config_path() -> PathBuf {
+	if let Ok(directory) = std::env::var("XDG_CONFIG_HOME") {
+		PathBuf::from(directory).join("bansheefinder/config.toml")
+	} else {
+		home_directory().join(".config/bansheefinder/config.toml")
+	}
+}
+
+pub fn data_directory() -> PathBuf {
+	if let Ok(directory) = std::env::var("XDG_DATA_HOME") {
+		PathBuf::from(directory).join("bansheefinder")
+	} else {
+		home_directory().join(".local/share/bansheefinder")
+	}
+}
+
+// nothing is stored until something is used, so every write makes sure the directory is there
+pub fn create_data_directory() -> std::io::Result<()> {
+	std::fs::create_dir_all(data_directory())
+}
+
+fn read_config() -> Config {
+	let contents = match std::fs::read_to_string(config_path()) {
+		Ok(contents) => contents,
+		// no config file, use the defaults
+		Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Config::default(),
+		Err(error) => {
+			report(Error::Read(config_path(), error));
+			return Config::default();
+		}
+	};
+
+	match toml::from_str(&contents) {
+		Ok(config) => config,
+		Err(error) => {
+			// the rest of the error points at the spot in the file over several lines
+			let message = match error.span() {
+				Some(span) => {
+					let line = contents[..span.start].matches('\n').count() + 1;
+					format!("line {}: {}", line, error.message())
+				}
+				None => error.message().to_string(),
+			};
+
+			report(Error::Config(message));
+			Config::default()
+		}
+	}
+}
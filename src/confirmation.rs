@@ -0,0 +1,96 @@
+use iced::{
+	widget::{self, column, container, text, text_input, Space},
+	Alignment, Border, Color, Element, Length, Padding, Task,
+};
+
+use crate::config::CONFIG;
+use crate::style::{font_scale, theme};
+
+// what has to be typed when confirming by enter alone is turned off
+const CONFIRMATION_WORD: &str = "yes";
+
+#[derive(Clone, Debug)]
+pub enum Message {
+	Typed(String),
+}
+
+pub struct View {
+	command: String,
+	input: String,
+	pub text_input: widget::Id,
+}
+
+impl View {
+	pub fn new() -> Self {
+		View {
+			command: String::new(),
+			input: String::new(),
+			text_input: widget::Id::unique(),
+		}
+	}
+
+	// shows the command that is about to run and clears anything typed for a previous one
+	pub fn ask(&mut self, command: String) {
+		self.command = command;
+		self.input = String::new();
+	}
+
+	pub fn is_confirmed(&self) -> bool {
+		!CONFIG.confirmation.require_typing || self.input.trim() == CONFIRMATION_WORD
+	}
+
+	pub fn update(&mut self, message: Message) -> Task<Message> {
+		match message {
+			Message::Typed(input) => {
+				self.input = input;
+			}
+		}
+
+		Task::none()
+	}
+
+	pub fn view(&self) -> Element<'_, Message> {
+		let theme = theme();
+		let hint = if CONFIG.confirmation.require_typing {
+			format!("type {} and press enter to run", CONFIRMATION_WORD)
+		} else {
+			String::from("press enter again to run, escape to go back")
+		};
+
+		column![
+			Space::new().height(Length::Fixed(5.0)),
+			container(
+				text("Run this command?")
+					.size(14.0 * font_scale())
+					.width(Length::Fill)
+			)
+			.width(Length::Fill)
+			.padding(Padding::default().left(7)), // [0, 0, 0, 7]
+			container(
+				text(&self.command)
+					.size(10.0 * font_scale())
+					.width(Length::Fill)
+					.color(theme.input_text)
+			)
+			.width(Length::Fill)
+			.padding(Padding::default().top(4).right(7).bottom(4).left(7)), // [4, 7, 4, 7]
+			text_input(&hint, &self.input)
+				.id(self.text_input.clone())
+				.size(12.0 * font_scale())
+				.on_input(Message::Typed)
+				.padding(Padding::default().top(4).right(7).bottom(4).left(7)) // [4, 7, 4, 7]
+				.style(move |_, _| text_input::Style {
+					background: theme.input_background.into(),
+					border: Border::default(),
+					icon: Color::BLACK,
+					placeholder: theme.subtitle,
+					value: theme.input_text,
+					selection: theme.highlight,
+				})
+		]
+		.padding(0)
+		.align_x(Alignment::Center)
+		.height(Length::Fill)
+		.into()
+	}
+}
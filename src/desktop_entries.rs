@@ -0,0 +1,124 @@
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::home_directory;
+
+lazy_static! {
+	// descriptions and icons both come from these, so the directories are only walked once
+	static ref DESKTOP_ENTRIES: Vec<DesktopEntry> = read_desktop_entries();
+}
+
+#[derive(Clone, Debug)]
+pub struct DesktopEntry {
+	pub id: String,              // file name without the .desktop extension
+	pub program: Option<String>, // file name of the program in Exec=
+	pub icon: Option<String>,
+	pub comment: Option<String>,
+}
+
+// $XDG_DATA_HOME followed by $XDG_DATA_DIRS, the order the freedesktop specs search data files in
+pub fn data_directories() -> Vec<PathBuf> {
+	let data_home = std::env::var("XDG_DATA_HOME")
+		.map(PathBuf::from)
+		.unwrap_or_else(|_| home_directory().join(".local/share"));
+	let data_dirs = std::env::var("XDG_DATA_DIRS")
+		.unwrap_or_else(|_| String::from("/usr/local/share:/usr/share"));
+
+	let mut output = vec![data_home];
+	output.extend(
+		data_dirs
+			.split(':')
+			.filter(|directory| !directory.is_empty())
+			.map(PathBuf::from),
+	);
+	output
+}
+
+// reads the groups of an ini style file, keys of the same group are merged
+pub fn parse_ini(contents: &str) -> HashMap<String, HashMap<String, String>> {
+	let mut groups: HashMap<String, HashMap<String, String>> = HashMap::new();
+	let mut group = String::new();
+	for line in contents.lines().map(str::trim) {
+		if line.starts_with('#') || line.is_empty() {
+			continue;
+		}
+
+		if let Some(name) = line
+			.strip_prefix('[')
+			.and_then(|line| line.strip_suffix(']'))
+		{
+			group = name.to_string();
+		} else if let Some((key, value)) = line.split_once('=') {
+			groups
+				.entry(group.clone())
+				.or_default()
+				.insert(key.trim().to_string(), value.trim().to_string());
+		}
+	}
+
+	groups
+}
+
+fn read_entry(path: &Path) -> Option<DesktopEntry> {
+	let groups = parse_ini(&std::fs::read_to_string(path).ok()?);
+	let entry = groups.get("Desktop Entry")?;
+	if entry.get("Hidden").is_some_and(|hidden| hidden == "true") {
+		return None;
+	}
+
+	let program = entry
+		.get("Exec")
+		.and_then(|exec| exec.split_whitespace().next())
+		.and_then(|program| Path::new(program.trim_matches('"')).file_name())
+		.map(|program| program.to_string_lossy().to_string());
+
+	Some(DesktopEntry {
+		id: path.file_stem()?.to_string_lossy().to_string(),
+		program,
+		icon: entry.get("Icon").cloned(),
+		comment: entry
+			.get("Comment")
+			.filter(|comment| !comment.is_empty())
+			.cloned(),
+	})
+}
+
+// the first directory that has a desktop file wins, like it does for application launchers
+fn read_desktop_entries() -> Vec<DesktopEntry> {
+	let mut ids = HashSet::new();
+	let mut output = Vec::new();
+	for directory in data_directories() {
+		let read_directory =
+			if let Ok(read_directory) = std::fs::read_dir(directory.join("applications")) {
+				read_directory
+			} else {
+				continue;
+			};
+
+		for file in read_directory.flatten() {
+			let path = file.path();
+			if path
+				.extension()
+				.is_none_or(|extension| extension != "desktop")
+			{
+				continue;
+			}
+
+			let id = path.file_stem().unwrap().to_string_lossy().to_string();
+			if !ids.insert(id) {
+				continue;
+			}
+
+			if let Some(entry) = read_entry(&path) {
+				output.push(entry);
+			}
+		}
+	}
+
+	output
+}
+
+pub fn desktop_entries() -> &'static [DesktopEntry] {
+	&DESKTOP_ENTRIES
+}
@@ -0,0 +1,61 @@
+use lazy_static::lazy_static;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// exit codes from sysexits.h, so scripts starting the finder can tell failures apart
+const EX_UNAVAILABLE: i32 = 69;
+const EX_IOERR: i32 = 74;
+const EX_CONFIG: i32 = 78;
+
+lazy_static! {
+	// errors found away from the window, e.g. while the config is loaded, wait here until it
+	// shows them
+	static ref REPORTED: Mutex<Vec<Error>> = Mutex::new(Vec::new());
+}
+
+#[derive(Debug)]
+pub enum Error {
+	Launch(String, io::Error), // the command that could not be run
+	Focus(io::Error),
+	Read(PathBuf, io::Error),
+	Write(PathBuf, io::Error),
+	Config(String),
+}
+
+impl Error {
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			Error::Launch(_, _) | Error::Focus(_) => EX_UNAVAILABLE,
+			Error::Read(_, _) | Error::Write(_, _) => EX_IOERR,
+			Error::Config(_) => EX_CONFIG,
+		}
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Launch(command, error) => write!(f, "Could not run {}: {}", command, error),
+			Error::Focus(error) => write!(f, "Could not focus the window: {}", error),
+			Error::Read(path, error) => write!(f, "Could not read {}: {}", path.display(), error),
+			Error::Write(path, error) => write!(f, "Could not write {}: {}", path.display(), error),
+			Error::Config(message) => write!(f, "Config: {}", message),
+		}
+	}
+}
+
+pub fn report(error: Error) {
+	REPORTED.lock().unwrap().push(error);
+}
+
+// for loading things the finder can do without, e.g. `read_symbol_frequency().unwrap_or_else(reported)`
+pub fn reported<T: Default>(error: Error) -> T {
+	report(error);
+	T::default()
+}
+
+pub fn take_reported() -> Vec<Error> {
+	std::mem::take(&mut *REPORTED.lock().unwrap())
+}
@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+
+// finds the git directory for a repository, following the gitdir file used by worktrees and
+// submodules
+pub fn git_directory(repository: &Path) -> Option<PathBuf> {
+	let dot_git = repository.join(".git");
+	if dot_git.is_dir() {
+		return Some(dot_git);
+	}
+
+	let contents = std::fs::read_to_string(&dot_git).ok()?;
+	let git_directory = contents.trim().strip_prefix("gitdir:")?.trim();
+	Some(repository.join(git_directory))
+}
+
+// reads the time of the most recent commit from the HEAD reflog, which is much cheaper than
+// running git log for every project
+pub fn last_commit_time(repository: &Path) -> Option<u64> {
+	let reflog = std::fs::read_to_string(git_directory(repository)?.join("logs/HEAD")).ok()?;
+
+	// lines that do not look like a reflog entry are skipped, not the whole repository
+	reflog
+		.lines()
+		.rev()
+		.find_map(|line| {
+			// <old> <new> <name> <email> <timestamp> <timezone>\t<message>
+			let (entry, message) = line.split_once('\t')?;
+			if !message.starts_with("commit") {
+				return None;
+			}
+
+			let mut fields = entry.rsplitn(3, ' ');
+			fields.next(); // timezone
+			fields.next()?.parse().ok()
+		})
+}
+
+#[derive(Debug, Default)]
+pub struct Status {
+	pub branch: String,
+	pub ahead: u32,
+	pub behind: u32,
+	pub dirty: bool,
+}
+
+// reads the checked out branch straight from HEAD, or the short commit hash when detached
+pub fn current_branch(repository: &Path) -> Option<String> {
+	let head = std::fs::read_to_string(git_directory(repository)?.join("HEAD")).ok()?;
+	let head = head.trim();
+	if let Some(reference) = head.strip_prefix("ref: ") {
+		Some(
+			reference
+				.strip_prefix("refs/heads/")
+				.unwrap_or(reference)
+				.to_string(),
+		)
+	} else {
+		Some(head.chars().take(7).collect())
+	}
+}
+
+pub fn status(repository: &Path) -> Option<Status> {
+	let output = std::process::Command::new("git")
+		.arg("-C")
+		.arg(repository)
+		.args([
+			"status",
+			"--porcelain=v2",
+			"--branch",
+			"--untracked-files=normal",
+		])
+		.output()
+		.ok()?;
+
+	if !output.status.success() {
+		return None;
+	}
+
+	let mut status = Status {
+		branch: current_branch(repository).unwrap_or_default(),
+		..Status::default()
+	};
+
+	for line in String::from_utf8_lossy(&output.stdout).lines() {
+		if let Some(ahead_behind) = line.strip_prefix("# branch.ab ") {
+			// # branch.ab +<ahead> -<behind>
+			let mut counts = ahead_behind.split(' ');
+			status.ahead = counts.next()?.trim_start_matches('+').parse().ok()?;
+			status.behind = counts.next()?.trim_start_matches('-').parse().ok()?;
+		} else if !line.starts_with('#') {
+			status.dirty = true;
+		}
+	}
+
+	Some(status)
+}
+
+pub fn relative_time(timestamp: u64) -> String {
+	let now = chrono::Local::now().timestamp() as u64;
+	let seconds = now.saturating_sub(timestamp);
+	if seconds < 60 {
+		String::from("just now")
+	} else if seconds < 60 * 60 {
+		format!("{}m ago", seconds / 60)
+	} else if seconds < 60 * 60 * 24 {
+		format!("{}h ago", seconds / (60 * 60))
+	} else if seconds < 60 * 60 * 24 * 30 {
+		format!("{}d ago", seconds / (60 * 60 * 24))
+	} else if seconds < 60 * 60 * 24 * 365 {
+		format!("{}mo ago", seconds / (60 * 60 * 24 * 30))
+	} else {
+		format!("{}y ago", seconds / (60 * 60 * 24 * 365))
+	}
+}
+
+// one line summary of a repository, e.g. "main* ↑1 ↓2 · 3d ago"
+pub fn summary(repository: &Path) -> Option<String> {
+	let status = status(repository)?;
+
+	let mut summary = status.branch;
+	if status.dirty {
+		summary.push('*');
+	}
+
+	if status.ahead > 0 {
+		summary.push_str(&format!(" ↑{}", status.ahead));
+	}
+
+	if status.behind > 0 {
+		summary.push_str(&format!(" ↓{}", status.behind));
+	}
+
+	if let Some(timestamp) = last_commit_time(repository) {
+		summary.push_str(&format!(" · {}", relative_time(timestamp)));
+	}
+
+	Some(summary)
+}
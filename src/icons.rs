@@ -0,0 +1,356 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::{create_data_directory, data_directory, home_directory, CONFIG};
+use crate::desktop_entries::{data_directories, desktop_entries, parse_ini};
+use crate::error::{report, Error};
+
+// iced cannot draw xpm, so those are treated as if the theme did not have the icon
+const EXTENSIONS: [&str; 2] = ["png", "svg"];
+
+#[derive(Default, Deserialize, Serialize)]
+struct IconCache {
+	theme: String,
+	timestamp: u64,
+	icons: HashMap<String, Option<PathBuf>>, // misses are cached too, most programs have no icon
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DirectoryType {
+	Fixed,
+	Scalable,
+	Threshold,
+}
+
+#[derive(Clone, Debug)]
+struct ThemeDirectory {
+	path: String,
+	size: u16,
+	min_size: u16,
+	max_size: u16,
+	threshold: u16,
+	kind: DirectoryType,
+}
+
+impl ThemeDirectory {
+	fn matches_size(&self, size: u16) -> bool {
+		match self.kind {
+			DirectoryType::Fixed => self.size == size,
+			DirectoryType::Scalable => self.min_size <= size && size <= self.max_size,
+			DirectoryType::Threshold => {
+				self.size.saturating_sub(self.threshold) <= size && size <= self.size + self.threshold
+			}
+		}
+	}
+
+	fn size_distance(&self, size: u16) -> u16 {
+		let (min, max) = match self.kind {
+			DirectoryType::Fixed => (self.size, self.size),
+			DirectoryType::Scalable => (self.min_size, self.max_size),
+			DirectoryType::Threshold => {
+				(self.size.saturating_sub(self.threshold), self.size + self.threshold)
+			}
+		};
+
+		if size < min {
+			min - size
+		} else {
+			size.saturating_sub(max)
+		}
+	}
+}
+
+struct Theme {
+	directories: Vec<ThemeDirectory>,
+	inherits: Vec<String>,
+	roots: Vec<PathBuf>, // every base directory that has a folder for this theme
+}
+
+// base directories from the icon theme spec, in lookup order
+fn base_directories() -> Vec<PathBuf> {
+	let mut output = vec![home_directory().join(".icons")];
+	output.extend(data_directories().iter().map(|directory| directory.join("icons")));
+	output.push(PathBuf::from("/usr/share/pixmaps"));
+	output
+}
+
+// the theme from the config file, otherwise the one gtk is set up to use
+pub fn theme_name() -> String {
+	if let Some(theme) = CONFIG.icons.theme.as_ref() {
+		return theme.clone();
+	}
+
+	let settings = std::env::var("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.unwrap_or_else(|_| home_directory().join(".config"))
+		.join("gtk-3.0/settings.ini");
+
+	std::fs::read_to_string(settings)
+		.ok()
+		.and_then(|contents| {
+			contents.lines().find_map(|line| {
+				let (key, value) = line.split_once('=')?;
+				(key.trim() == "gtk-icon-theme-name").then(|| value.trim().trim_matches('"').to_string())
+			})
+		})
+		.unwrap_or_else(|| String::from("hicolor"))
+}
+
+fn load_theme(name: &str, bases: &[PathBuf]) -> Option<Theme> {
+	let roots = bases
+		.iter()
+		.map(|base| base.join(name))
+		.filter(|root| root.is_dir())
+		.collect::<Vec<PathBuf>>();
+
+	let index = roots
+		.iter()
+		.find_map(|root| std::fs::read_to_string(root.join("index.theme")).ok())?;
+	let groups = parse_ini(&index);
+	let theme = groups.get("Icon Theme")?;
+
+	let list = |key: &str| {
+		theme
+			.get(key)
+			.map(|value| {
+				value
+					.split(',')
+					.map(|entry| entry.trim().to_string())
+					.filter(|entry| !entry.is_empty())
+					.collect::<Vec<String>>()
+			})
+			.unwrap_or_default()
+	};
+
+	let mut directory_names = list("Directories");
+	directory_names.extend(list("ScaledDirectories"));
+
+	let directories = directory_names
+		.into_iter()
+		.filter_map(|path| {
+			let group = groups.get(&path)?;
+			let number = |key: &str| group.get(key).and_then(|value| value.parse::<u16>().ok());
+
+			// icons meant for hidpi screens only are not worth drawing at our size
+			if number("Scale").unwrap_or(1) != 1 {
+				return None;
+			}
+
+			let size = number("Size")?;
+			Some(ThemeDirectory {
+				kind: match group.get("Type").map(String::as_str) {
+					Some("Fixed") => DirectoryType::Fixed,
+					Some("Scalable") => DirectoryType::Scalable,
+					_ => DirectoryType::Threshold,
+				},
+				min_size: number("MinSize").unwrap_or(size),
+				max_size: number("MaxSize").unwrap_or(size),
+				threshold: number("Threshold").unwrap_or(2),
+				path,
+				size,
+			})
+		})
+		.collect();
+
+	let inherits = list("Inherits");
+	Some(Theme {
+		directories,
+		inherits,
+		roots,
+	})
+}
+
+struct Resolver {
+	bases: Vec<PathBuf>,
+	desktop_icons: Option<HashMap<String, String>>, // program or desktop file id to its Icon= key
+	size: u16,
+	themes: HashMap<String, Option<Theme>>,
+}
+
+impl Resolver {
+	fn new() -> Self {
+		Resolver {
+			bases: base_directories(),
+			desktop_icons: None,
+			size: CONFIG.icons.size,
+			themes: HashMap::new(),
+		}
+	}
+
+	// desktop files are only read once something actually needs to be resolved
+	fn desktop_icon(&mut self, name: &str) -> Option<String> {
+		if self.desktop_icons.is_none() {
+			self.desktop_icons = Some(read_desktop_icons());
+		}
+
+		self.desktop_icons.as_ref().unwrap().get(name).cloned()
+	}
+
+	fn theme(&mut self, name: &str) -> Option<&Theme> {
+		if !self.themes.contains_key(name) {
+			let theme = load_theme(name, &self.bases);
+			self.themes.insert(name.to_string(), theme);
+		}
+
+		self.themes.get(name).unwrap().as_ref()
+	}
+
+	fn lookup_in_theme(&mut self, icon: &str, theme: &str) -> Option<PathBuf> {
+		let size = self.size;
+		let theme = self.theme(theme)?;
+
+		let mut closest: Option<(u16, PathBuf)> = None;
+		for directory in theme.directories.iter() {
+			for root in theme.roots.iter() {
+				for extension in EXTENSIONS {
+					let path = root.join(&directory.path).join(format!("{}.{}", icon, extension));
+					if !path.is_file() {
+						continue;
+					}
+
+					if directory.matches_size(size) {
+						return Some(path);
+					}
+
+					let distance = directory.size_distance(size);
+					if closest.as_ref().is_none_or(|(closest, _)| distance < *closest) {
+						closest = Some((distance, path));
+					}
+				}
+			}
+		}
+
+		closest.map(|(_, path)| path)
+	}
+
+	// walks the inheritance tree depth first, the way the spec describes it
+	fn lookup_with_parents(
+		&mut self,
+		icon: &str,
+		theme: &str,
+		visited: &mut HashSet<String>,
+	) -> Option<PathBuf> {
+		if !visited.insert(theme.to_string()) {
+			return None;
+		}
+
+		if let Some(path) = self.lookup_in_theme(icon, theme) {
+			return Some(path);
+		}
+
+		let parents = self
+			.theme(theme)
+			.map(|theme| theme.inherits.clone())
+			.unwrap_or_default();
+		parents
+			.iter()
+			.find_map(|parent| self.lookup_with_parents(icon, parent, visited))
+	}
+
+	fn lookup_fallback(&self, icon: &str) -> Option<PathBuf> {
+		self.bases.iter().find_map(|base| {
+			EXTENSIONS
+				.iter()
+				.map(|extension| base.join(format!("{}.{}", icon, extension)))
+				.find(|path| path.is_file())
+		})
+	}
+
+	fn lookup(&mut self, icon: &str, theme: &str) -> Option<PathBuf> {
+		let path = Path::new(icon);
+		if path.is_absolute() {
+			return path.is_file().then(|| path.to_path_buf());
+		}
+
+		let mut visited = HashSet::new();
+		self.lookup_with_parents(icon, theme, &mut visited)
+			.or_else(|| self.lookup_with_parents(icon, "hicolor", &mut visited))
+			.or_else(|| self.lookup_fallback(icon))
+	}
+
+	// a desktop file knows the icon of a program better than a guess from its name does
+	fn resolve(&mut self, name: &str, theme: &str) -> Option<PathBuf> {
+		if let Some(icon) = self.desktop_icon(name)
+			&& let Some(path) = self.lookup(&icon, theme)
+		{
+			return Some(path);
+		}
+
+		self.lookup(name, theme)
+	}
+}
+
+// maps both the desktop file id and the program it runs to the icon of the entry
+fn read_desktop_icons() -> HashMap<String, String> {
+	let mut output = HashMap::new();
+	for entry in desktop_entries() {
+		if let Some(icon) = entry.icon.as_ref() {
+			if let Some(program) = entry.program.as_ref() {
+				output.entry(program.clone()).or_insert(icon.clone());
+			}
+
+			output.entry(entry.id.clone()).or_insert(icon.clone());
+		}
+	}
+
+	output
+}
+
+fn cache_path() -> PathBuf {
+	data_directory().join("icons.json")
+}
+
+fn read_cache(theme: &str) -> IconCache {
+	let now = chrono::Local::now().timestamp() as u64;
+	std::fs::read_to_string(cache_path())
+		.ok()
+		.and_then(|contents| serde_json::from_str::<IconCache>(&contents).ok())
+		.filter(|cache| {
+			cache.theme == theme && now.saturating_sub(cache.timestamp) < CONFIG.icons.cache_seconds
+		})
+		.unwrap_or_else(|| IconCache {
+			theme: theme.to_string(),
+			timestamp: now,
+			icons: HashMap::new(),
+		})
+}
+
+// resolves icon names to image files, going through the theme only for names that are not cached
+pub fn resolve(names: Vec<String>) -> Vec<(String, Option<PathBuf>)> {
+	let theme = theme_name();
+	let mut cache = read_cache(&theme);
+	let mut resolver = Resolver::new();
+	let mut changed = false;
+
+	let output = names
+		.into_iter()
+		.map(|name| {
+			// a cached path can disappear when the program that shipped it is removed
+			let cached = cache
+				.icons
+				.get(&name)
+				.filter(|path| path.as_ref().is_none_or(|path| path.is_file()));
+
+			if let Some(path) = cached {
+				return (name, path.clone());
+			}
+
+			let path = resolver.resolve(&name, &theme);
+			cache.icons.insert(name.clone(), path.clone());
+			changed = true;
+			(name, path)
+		})
+		.collect();
+
+	if changed
+		&& let Err(error) = serde_json::to_string(&cache)
+			.map_err(std::io::Error::other)
+			.and_then(|contents| create_data_directory().map(|_| contents))
+			.and_then(|contents| std::fs::write(cache_path(), contents))
+	{
+		report(Error::Write(cache_path(), error));
+	}
+
+	output
+}
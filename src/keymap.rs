@@ -0,0 +1,287 @@
+use iced::keyboard::{key::Named, Key, Modifiers};
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::config::{KeymapPreset, CONFIG};
+use crate::error::{report, Error};
+
+lazy_static! {
+	pub static ref KEYMAP: Keymap = Keymap::from_config();
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+	Next,
+	Prev,
+	PageDown,
+	PageUp,
+	First,
+	Last,
+	Complete,
+	Accept,
+	AcceptAlt, // shows the secondary actions of the selected item
+	RunInTerminal,
+	Copy,
+	DeleteHistoryEntry,
+	Cancel,
+}
+
+const DEFAULT_BINDINGS: [(&str, Action); 13] = [
+	("down", Action::Next),
+	("up", Action::Prev),
+	("pagedown", Action::PageDown),
+	("pageup", Action::PageUp),
+	// plain home and end are left to the text input for moving the caret
+	("ctrl+home", Action::First),
+	("ctrl+end", Action::Last),
+	("tab", Action::Complete),
+	("enter", Action::Accept),
+	("shift+enter", Action::AcceptAlt),
+	("ctrl+enter", Action::RunInTerminal),
+	("ctrl+c", Action::Copy),
+	("shift+delete", Action::DeleteHistoryEntry),
+	("escape", Action::Cancel),
+];
+
+// the presets only add to the default bindings, the arrow keys keep working in all of them. ctrl+v
+// is left to pasting, so emacs only has a chord for paging up
+const EMACS_BINDINGS: [(&str, Action); 5] = [
+	("ctrl+n", Action::Next),
+	("ctrl+p", Action::Prev),
+	("alt+v", Action::PageUp),
+	("ctrl+j", Action::Accept),
+	("ctrl+g", Action::Cancel),
+];
+
+const VIM_BINDINGS: [(&str, Action); 6] = [
+	("ctrl+j", Action::Next),
+	("ctrl+k", Action::Prev),
+	("ctrl+d", Action::PageDown),
+	("ctrl+u", Action::PageUp),
+	("ctrl+l", Action::Complete),
+	("ctrl+[", Action::Cancel),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chord {
+	key: Key,
+	modifiers: Modifiers,
+}
+
+impl Chord {
+	// parses chords like "ctrl+n", "shift+enter" or "pagedown", ignoring case
+	pub fn parse(chord: &str) -> Option<Chord> {
+		let chord = chord.to_lowercase();
+		let mut parts = chord.split('+').map(str::trim).collect::<Vec<&str>>();
+
+		// "ctrl++" binds the plus key itself
+		let key = if chord.ends_with("++") {
+			parts.truncate(parts.len() - 2);
+			"+"
+		} else {
+			parts.pop()?
+		};
+
+		let mut modifiers = Modifiers::empty();
+		for modifier in parts {
+			modifiers |= match modifier {
+				"ctrl" | "control" => Modifiers::CTRL,
+				"alt" => Modifiers::ALT,
+				"shift" => Modifiers::SHIFT,
+				"super" | "logo" | "meta" => Modifiers::LOGO,
+				_ => return None,
+			};
+		}
+
+		let key = match key {
+			"enter" | "return" => Key::Named(Named::Enter),
+			"escape" | "esc" => Key::Named(Named::Escape),
+			"tab" => Key::Named(Named::Tab),
+			"up" => Key::Named(Named::ArrowUp),
+			"down" => Key::Named(Named::ArrowDown),
+			"left" => Key::Named(Named::ArrowLeft),
+			"right" => Key::Named(Named::ArrowRight),
+			"pageup" => Key::Named(Named::PageUp),
+			"pagedown" => Key::Named(Named::PageDown),
+			"home" => Key::Named(Named::Home),
+			"end" => Key::Named(Named::End),
+			"delete" | "del" => Key::Named(Named::Delete),
+			"backspace" => Key::Named(Named::Backspace),
+			"space" => Key::Named(Named::Space),
+			key if key.chars().count() == 1 => Key::Character(key.into()),
+			_ => return None,
+		};
+
+		Some(Chord { key, modifiers })
+	}
+
+	fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+		let same_key = match (&self.key, key) {
+			(Key::Character(this), Key::Character(other)) => this.as_str() == other.to_lowercase(),
+			(this, other) => this == other,
+		};
+
+		// symbols like + often need shift to be typed at all, so it does not tell chords apart
+		if let Key::Character(character) = &self.key
+			&& !character.chars().any(char::is_alphanumeric)
+		{
+			return same_key && self.modifiers - Modifiers::SHIFT == modifiers - Modifiers::SHIFT;
+		}
+
+		same_key && self.modifiers == modifiers
+	}
+}
+
+// keys the focused text input acts on itself, like ctrl+v or home. a binding on one of them must
+// not fire as well once the input has used the key. ctrl+home and ctrl+end only move the caret
+// further than home and end, and shift+delete is left alone for removing history entries
+pub fn edits_text(key: &Key, modifiers: Modifiers) -> bool {
+	match key {
+		Key::Named(Named::Home | Named::End) => !modifiers.command(),
+		Key::Named(Named::Delete) => !modifiers.shift(),
+		Key::Named(Named::ArrowLeft | Named::ArrowRight | Named::Backspace) => true,
+		Key::Character(character) => {
+			modifiers.command() && matches!(character.to_lowercase().as_str(), "a" | "c" | "v" | "x")
+		}
+		_ => false,
+	}
+}
+
+pub struct Keymap {
+	bindings: Vec<(Chord, Action)>,
+}
+
+impl Keymap {
+	fn from_config() -> Self {
+		let config = &CONFIG.keybindings;
+		let preset: &[(&str, Action)] = match config.preset {
+			KeymapPreset::Default => &[],
+			KeymapPreset::Emacs => &EMACS_BINDINGS,
+			KeymapPreset::Vim => &VIM_BINDINGS,
+		};
+
+		let mut keymap = Keymap {
+			bindings: Vec::new(),
+		};
+
+		for (chord, action) in DEFAULT_BINDINGS.iter().chain(preset.iter()) {
+			keymap.bind(Chord::parse(chord).unwrap(), *action);
+		}
+
+		for (chord, action) in config.bindings.iter() {
+			if let Some(chord) = Chord::parse(chord) {
+				keymap.bind(chord, *action);
+			} else {
+				report(Error::Config(format!(
+					"could not parse key binding {:?}",
+					chord
+				)));
+			}
+		}
+
+		keymap
+	}
+
+	// a chord only ever does one thing, binding it again replaces what it did before
+	fn bind(&mut self, chord: Chord, action: Action) {
+		self.bindings.retain(|(bound, _)| bound != &chord);
+		self.bindings.push((chord, action));
+	}
+
+	pub fn action(&self, key: &Key, modifiers: Modifiers) -> Option<Action> {
+		self.bindings
+			.iter()
+			.find(|(chord, _)| chord.matches(key, modifiers))
+			.map(|(_, action)| *action)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn character(key: &str) -> Key {
+		Key::Character(key.into())
+	}
+
+	#[test]
+	fn parses_chords() {
+		let chord = Chord::parse("Ctrl+Shift+N").unwrap();
+		assert_eq!(chord.key, character("n"));
+		assert_eq!(chord.modifiers, Modifiers::CTRL | Modifiers::SHIFT);
+
+		let chord = Chord::parse("pagedown").unwrap();
+		assert_eq!(chord.key, Key::Named(Named::PageDown));
+		assert_eq!(chord.modifiers, Modifiers::empty());
+	}
+
+	#[test]
+	fn parses_plus_and_bracket_keys() {
+		let chord = Chord::parse("ctrl++").unwrap();
+		assert_eq!(chord.key, character("+"));
+		assert_eq!(chord.modifiers, Modifiers::CTRL);
+
+		let chord = Chord::parse("ctrl+[").unwrap();
+		assert_eq!(chord.key, character("["));
+		assert_eq!(chord.modifiers, Modifiers::CTRL);
+	}
+
+	#[test]
+	fn rejects_unknown_chords() {
+		assert_eq!(Chord::parse("hyper+n"), None);
+		assert_eq!(Chord::parse("ctrl+nope"), None);
+		assert_eq!(Chord::parse(""), None);
+	}
+
+	#[test]
+	fn binding_a_chord_again_replaces_it() {
+		let mut keymap = Keymap {
+			bindings: Vec::new(),
+		};
+		keymap.bind(Chord::parse("ctrl+j").unwrap(), Action::Accept);
+		keymap.bind(Chord::parse("ctrl+k").unwrap(), Action::Prev);
+		keymap.bind(Chord::parse("ctrl+j").unwrap(), Action::Next);
+
+		assert_eq!(keymap.bindings.len(), 2);
+		assert_eq!(
+			keymap.action(&character("j"), Modifiers::CTRL),
+			Some(Action::Next)
+		);
+		assert_eq!(
+			keymap.action(&character("K"), Modifiers::CTRL),
+			Some(Action::Prev)
+		);
+		assert_eq!(keymap.action(&character("j"), Modifiers::empty()), None);
+	}
+
+	#[test]
+	fn symbol_chords_ignore_shift() {
+		let mut keymap = Keymap {
+			bindings: Vec::new(),
+		};
+		keymap.bind(Chord::parse("ctrl++").unwrap(), Action::Next);
+		keymap.bind(Chord::parse("ctrl+shift+n").unwrap(), Action::Prev);
+
+		// + is typed with shift on most layouts and without it on the keypad
+		assert_eq!(keymap.action(&character("+"), Modifiers::CTRL | Modifiers::SHIFT), Some(Action::Next));
+		assert_eq!(keymap.action(&character("+"), Modifiers::CTRL), Some(Action::Next));
+
+		// letters still need the shift they were bound with
+		assert_eq!(keymap.action(&character("N"), Modifiers::CTRL | Modifiers::SHIFT), Some(Action::Prev));
+		assert_eq!(keymap.action(&character("n"), Modifiers::CTRL), None);
+	}
+
+	#[test]
+	fn knows_which_keys_edit_text() {
+		assert!(edits_text(&character("v"), Modifiers::CTRL));
+		assert!(edits_text(&character("c"), Modifiers::CTRL));
+		assert!(edits_text(&Key::Named(Named::Home), Modifiers::empty()));
+		assert!(edits_text(&Key::Named(Named::Delete), Modifiers::empty()));
+
+		assert!(!edits_text(&character("n"), Modifiers::CTRL));
+		assert!(!edits_text(&Key::Named(Named::Home), Modifiers::CTRL));
+		assert!(!edits_text(&Key::Named(Named::Delete), Modifiers::SHIFT));
+		assert!(!edits_text(&Key::Named(Named::Enter), Modifiers::empty()));
+	}
+}
@@ -1,30 +1,89 @@
 use chrono::Local;
+use std::io::Write;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::path_interpreter::{read_command_frequency, write_command_frequency, ProgramFrequency};
+use crate::autocomplete::{ProjectAction, SessionAction, UnitAction, UnitScope};
+use crate::compositor;
+use crate::config::{expand_home, find_command, CONFIG};
+use crate::error::{reported, Error};
+use crate::shell::expand_command;
+use crate::systemd;
+use crate::path_interpreter::{
+	read_command_frequency, read_project_frequency, read_symbol_frequency, write_command_frequency,
+	write_project_frequency, write_symbol_frequency, ProgramFrequency, ProgramFrequencyMap,
+};
 
-pub fn update_frequency(program: &String) {
-	let mut frequency = read_command_frequency();
+pub fn update_frequency(program: &str) -> Result<(), Error> {
+	let mut frequency = read_command_frequency()?;
+	increment_frequency(&mut frequency, program);
+	write_command_frequency(frequency)
+}
+
+pub fn update_project_frequency(project: &str) -> Result<(), Error> {
+	let mut frequency = read_project_frequency()?;
+	increment_frequency(&mut frequency, project);
+	write_project_frequency(frequency)
+}
+
+pub fn update_symbol_frequency(symbol: &str) -> Result<(), Error> {
+	let mut frequency = read_symbol_frequency()?;
+	increment_frequency(&mut frequency, symbol);
+	write_symbol_frequency(frequency)
+}
+
+fn increment_frequency(frequency: &mut ProgramFrequencyMap, program: &str) {
 	let default = ProgramFrequency::default();
 	let program_frequency = frequency.map.get(program).unwrap_or(&default);
 	frequency.map.insert(
-		program.clone(),
+		program.to_string(),
 		ProgramFrequency {
 			count: program_frequency.count + 1,
 			timestamp: Local::now().timestamp() as u64,
 		},
 	);
-	write_command_frequency(frequency);
 }
 
-pub fn launch_program(program: String, base_command: Option<String>) {
-	if program.len() == 0 {
-		return;
-	}
+// a command that is still running after this long is taken to have started
+const STARTUP_TIME: Duration = Duration::from_millis(250);
 
-	if let Some(base_command) = base_command {
-		update_frequency(&base_command);
+fn exit_error(shown: &str, status: ExitStatus) -> Error {
+	let reason = match status.code() {
+		Some(127) => String::from("command not found"),
+		_ => format!("exited with {}", status),
+	};
+
+	Error::Launch(shown.to_string(), std::io::Error::other(reason))
+}
+
+// runs a shell command in the background, the error names the command as it was shown to the user.
+// sh itself always starts, so a command that fails right away counts as not launched
+fn spawn(process: &mut Command, shown: &str) -> Result<(), Error> {
+	let child = process
+		.spawn()
+		.map_err(|error| Error::Launch(shown.to_string(), error))?;
+	wait_for_start(child, shown)
+}
+
+fn wait_for_start(mut child: Child, shown: &str) -> Result<(), Error> {
+	let launch_error = |error| Error::Launch(shown.to_string(), error);
+	let started = Instant::now();
+	while started.elapsed() < STARTUP_TIME {
+		match child.try_wait().map_err(launch_error)? {
+			Some(status) if status.success() => return Ok(()),
+			Some(status) => return Err(exit_error(shown, status)),
+			None => thread::sleep(Duration::from_millis(10)),
+		}
 	}
 
+	Ok(())
+}
+
+// hands a command to the compositor, or whatever BANSHEEFINDER3_LAUNCH_COMMAND says, so it ends
+// up in the same session as programs started any other way
+fn hand_off(command: &str, shown: &str) -> Result<(), Error> {
 	let launch_command = if let Ok(launch_command) = std::env::var("BANSHEEFINDER3_LAUNCH_COMMAND")
 	{
 		launch_command
@@ -32,37 +91,225 @@ pub fn launch_program(program: String, base_command: Option<String>) {
 		"niri msg action spawn -- ".to_string()
 	};
 
-	let result = std::process::Command::new("sh")
+	// the launch command hands the program off and returns, so its status says if that worked
+	let output = Command::new("sh")
 		.arg("-c")
-		.arg(format!("{} {}", launch_command, program))
-		.output();
+		.arg(format!("{} {}", launch_command, command))
+		.output()
+		.map_err(|error| Error::Launch(shown.to_string(), error))?;
 
-	if let Err(error) = result {
-		eprintln!("could not launch {:?}", error);
+	if !output.status.success() {
+		return Err(match String::from_utf8_lossy(&output.stderr).trim() {
+			"" => exit_error(shown, output.status),
+			stderr => Error::Launch(shown.to_string(), std::io::Error::other(stderr.to_string())),
+		});
 	}
 
-	std::process::exit(1);
+	Ok(())
 }
 
-pub fn launch_program_sudo(program: String, base_command: Option<String>, password: String) {
-	if program.len() == 0 {
-		return;
+// programs are only counted once they have actually started. the launch is what matters, a
+// frequency that could not be saved is reported without keeping the finder open
+fn count_launch(base_command: Option<String>) -> Result<(), Error> {
+	if let Some(base_command) = base_command {
+		update_frequency(&base_command).unwrap_or_else(reported);
 	}
 
-	if let Some(base_command) = base_command {
-		update_frequency(&base_command);
+	Ok(())
+}
+
+pub fn launch_program(program: String, base_command: Option<String>) -> Result<(), Error> {
+	hand_off(&expand_command(&program), &program)?;
+	count_launch(base_command)
+}
+
+pub fn launch_program_sudo(
+	program: String,
+	base_command: Option<String>,
+	password: String,
+) -> Result<(), Error> {
+	let command = expand_command(&program[5..]); // remove sudo preamble
+
+	// sudo takes a while to turn down a wrong password, so it is checked before anything runs
+	let accepted = with_password(
+		Command::new("sudo").arg("-S").arg("-v").stderr(Stdio::null()),
+		&password,
+	)
+	.and_then(|mut child| child.wait())
+	.map_err(|error| Error::Launch(program.clone(), error))?;
+	if !accepted.success() {
+		return Err(Error::Launch(
+			program,
+			std::io::Error::other("sudo did not accept the password"),
+		));
 	}
 
-	let program = program[5..].to_string(); // remove sudo preamble
+	// -k has sudo read the password again instead of leaving it on the command's input
+	let child = with_password(
+		Command::new("sudo")
+			.arg("-S")
+			.arg("-k")
+			.arg("sh")
+			.arg("-c")
+			.arg(&command),
+		&password,
+	)
+	.map_err(|error| Error::Launch(program.clone(), error))?;
+	wait_for_start(child, &program)?;
 
-	let result = std::process::Command::new("sh")
-		.arg("-c")
-		.arg(format!("echo \"{}\" | sudo -S {}", password, program)) // TODO better password piping
-		.spawn();
+	count_launch(base_command)
+}
+
+// the password is written to sudo's input, so it is never part of a command line that the shell
+// could run or ps could show
+fn with_password(process: &mut Command, password: &str) -> std::io::Result<Child> {
+	let mut child = process.stdin(Stdio::piped()).spawn()?;
+	if let Some(mut stdin) = child.stdin.take() {
+		stdin.write_all(format!("{}\n", password).as_bytes())?;
+	}
+
+	Ok(child)
+}
+
+// keeps a shell open after the command exits so its output can still be read
+pub fn launch_in_terminal(program: String, base_command: Option<String>) -> Result<(), Error> {
+	let command = format!("{}; exec \"${{SHELL:-sh}}\"", expand_command(&program));
+	spawn(
+		Command::new("sh")
+			.arg("-c")
+			.arg(format!(
+				"{} sh -c {}",
+				CONFIG.shell.terminal,
+				shell_quote(&command)
+			))
+			.process_group(0),
+		&program,
+	)?;
+
+	count_launch(base_command)
+}
 
-	if let Err(error) = result {
-		eprintln!("could not launch {:?}", error);
+// wraps a string in single quotes so it survives being passed through sh -c
+pub fn shell_quote(string: &str) -> String {
+	format!("'{}'", string.replace('\'', "'\\''"))
+}
+
+pub fn launch_project(
+	path: String,
+	project: Option<String>,
+	action: ProjectAction,
+) -> Result<(), Error> {
+	let command = CONFIG
+		.projects
+		.actions
+		.template(action)
+		.replace("{path}", &shell_quote(&path));
+
+	// spawn in a new process group from inside the project directory so the program outlives us
+	spawn(
+		Command::new("sh")
+			.arg("-c")
+			.arg(&command)
+			.current_dir(&path)
+			.process_group(0),
+		&command,
+	)?;
+
+	if let Some(project) = project {
+		update_project_frequency(&project).unwrap_or_else(reported);
+	}
+
+	Ok(())
+}
+
+pub fn launch_custom(label: String) -> Result<(), Error> {
+	let command = if let Some(command) = find_command(&label) {
+		command
+	} else {
+		return Err(Error::Config(format!("no command called {:?}", label)));
+	};
+
+	// the launch command starts it somewhere else, so the environment and directory have to be
+	// part of the command itself
+	let mut script = String::new();
+	for (key, value) in command.env.iter() {
+		script.push_str(&format!("export {}={}; ", key, shell_quote(value)));
+	}
+
+	if let Some(cwd) = command.cwd.as_ref() {
+		let cwd = expand_home(cwd);
+		script.push_str(&format!("cd {} || exit 1; ", shell_quote(&cwd.to_string_lossy())));
+	}
+
+	script.push_str(&command.command);
+	hand_off(&format!("sh -c {}", shell_quote(&script)), &label)?;
+
+	if command.frecency {
+		update_frequency(&label).unwrap_or_else(reported);
 	}
 
-	std::process::exit(1);
+	Ok(())
+}
+
+// session actions are left out of the frecency store on purpose, nobody wants reboot to be the
+// first suggestion
+pub fn run_session_action(action: SessionAction) -> Result<(), Error> {
+	let command = CONFIG.session.command(action);
+	spawn(
+		Command::new("sh").arg("-c").arg(&command).process_group(0),
+		&command,
+	)
+}
+
+pub fn run_unit_action(name: String, scope: UnitScope, action: UnitAction) -> Result<(), Error> {
+	spawn(
+		Command::new("sh")
+			.arg("-c")
+			.arg(systemd::terminal_command(&name, scope, action))
+			.process_group(0),
+		&format!("{} {}", action.label(), name),
+	)
+}
+
+pub fn focus_window(id: String) -> Result<(), Error> {
+	let compositor = if let Some(compositor) = compositor::detect() {
+		compositor
+	} else {
+		return Err(Error::Focus(std::io::Error::other(
+			"no supported compositor found",
+		)));
+	};
+
+	compositor.focus(&id).map_err(Error::Focus)
+}
+
+pub fn type_text(text: String) -> Result<(), Error> {
+	let command = CONFIG
+		.symbols
+		.type_command
+		.replace("{text}", &shell_quote(&text));
+
+	// typing has to wait until we are gone, otherwise the keys go to our own window. that is too
+	// late to notice a missing program, so it is looked up first
+	let program = command.split_whitespace().next().unwrap_or_default();
+	let found = Command::new("sh")
+		.arg("-c")
+		.arg(format!("command -v {}", shell_quote(program)))
+		.stdout(Stdio::null())
+		.status()
+		.map_err(|error| Error::Launch(command.clone(), error))?;
+	if !found.success() {
+		return Err(Error::Launch(
+			command,
+			std::io::Error::other("command not found"),
+		));
+	}
+
+	Command::new("sh")
+		.arg("-c")
+		.arg(format!("sleep 0.2; {}", command))
+		.process_group(0)
+		.spawn()
+		.map(|_| ())
+		.map_err(|error| Error::Launch(command, error))
 }
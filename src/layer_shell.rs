@@ -0,0 +1,132 @@
+use iced::{Element, Subscription, Task};
+use iced_layershell::reexport::{Anchor, KeyboardInteractivity, Layer};
+use iced_layershell::settings::{LayerShellSettings, Settings, StartMode};
+use iced_layershell::to_layer_message;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::{Connection, Dispatch, QueueHandle};
+
+use crate::config::{Edge, CONFIG};
+use crate::style;
+use crate::{Message, Window};
+
+// the layer shell runtime has messages of its own for changing the surface, ours are wrapped
+#[to_layer_message]
+#[derive(Debug)]
+pub enum LayerMessage {
+	App(Message),
+}
+
+// only used for looking at the globals of the compositor
+struct Probe;
+
+impl Dispatch<WlRegistry, GlobalListContents> for Probe {
+	fn event(
+		_: &mut Self,
+		_: &WlRegistry,
+		_: wl_registry::Event,
+		_: &GlobalListContents,
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+	}
+}
+
+// compositors without wlr-layer-shell, like gnome, and x11 sessions get a regular window instead
+pub fn is_supported() -> bool {
+	let connection = if let Ok(connection) = Connection::connect_to_env() {
+		connection
+	} else {
+		return false;
+	};
+
+	registry_queue_init::<Probe>(&connection).is_ok_and(|(globals, _)| {
+		globals.contents().with_list(|list| {
+			list.iter()
+				.any(|global| global.interface == "zwlr_layer_shell_v1")
+		})
+	})
+}
+
+// the surface is sized by us, so it has to follow along when auto_fit changes the height
+fn resize(window: &Window, previous_height: f32) -> Task<LayerMessage> {
+	if window.window_height == previous_height {
+		return Task::none();
+	}
+
+	Task::done(LayerMessage::SizeChange((
+		CONFIG.window.width as u32,
+		window.window_height as u32,
+	)))
+}
+
+fn boot() -> (Window, Task<LayerMessage>) {
+	let (window, task) = Window::start(true);
+	let resize = resize(&window, CONFIG.window.height);
+	(window, Task::batch([task.map(LayerMessage::App), resize]))
+}
+
+fn update(window: &mut Window, message: LayerMessage) -> Task<LayerMessage> {
+	// the messages added by to_layer_message are taken care of before they get here
+	let message = if let LayerMessage::App(message) = message {
+		message
+	} else {
+		return Task::none();
+	};
+
+	let height = window.window_height;
+	let task = window.update(message).map(LayerMessage::App);
+	Task::batch([task, resize(window, height)])
+}
+
+fn view(window: &Window) -> Element<'_, LayerMessage> {
+	window.view().map(LayerMessage::App)
+}
+
+fn subscription(window: &Window) -> Subscription<LayerMessage> {
+	window.subscription().map(LayerMessage::App)
+}
+
+pub fn run() -> Result<(), iced_layershell::Error> {
+	let anchor = CONFIG
+		.layer_shell
+		.anchor
+		.iter()
+		.fold(Anchor::empty(), |anchor, edge| {
+			anchor
+				| match edge {
+					Edge::Top => Anchor::Top,
+					Edge::Right => Anchor::Right,
+					Edge::Bottom => Anchor::Bottom,
+					Edge::Left => Anchor::Left,
+				}
+		});
+	let [top, right, bottom, left] = CONFIG.layer_shell.margin;
+
+	// the settings replace the fonts, so they have to come before them
+	let mut application = iced_layershell::application(boot, "bansheefinder3", update, view)
+		.settings(Settings {
+			layer_settings: LayerShellSettings {
+				anchor,
+				layer: Layer::Overlay,
+				exclusive_zone: 0, // keep clear of panels, without pushing anything away
+				size: Some((CONFIG.window.width as u32, CONFIG.window.height as u32)),
+				margin: (top, right, bottom, left),
+				keyboard_interactivity: KeyboardInteractivity::Exclusive,
+				start_mode: StartMode::Active,
+				events_transparent: false,
+			},
+			default_font: style::font(),
+			antialiasing: CONFIG.window.antialiasing,
+			..Settings::default()
+		})
+		.font(include_bytes!("../fonts/MaterialIcons-Regular.ttf"))
+		.scale_factor(|_| CONFIG.window.scale)
+		.subscription(subscription);
+
+	if let Some(font) = style::bundled_font() {
+		application = application.font(font);
+	}
+
+	application.run()
+}
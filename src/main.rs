@@ -1,168 +1,536 @@
 mod autocomplete;
+mod calculator;
+mod clipboard;
+mod clipboard_history;
+mod compositor;
+mod config;
+mod confirmation;
+mod desktop_entries;
+mod error;
+mod git;
+mod icons;
+mod keymap;
 mod launcher;
+mod layer_shell;
 mod path_interpreter;
 mod programs_list;
+mod shell;
 mod style;
 mod sudo_password;
+mod symbols;
+mod systemd;
 
+use config::{find_command, SymbolAction, CONFIG};
+use error::Error;
 use iced::{
-	font::{Family, Stretch, Style, Weight},
-	keyboard::{self, key::Named, Key},
-	widget::{container, operation::focus},
-	Border, Element, Font, Length, Shadow, Subscription, Task,
+	event,
+	keyboard, mouse, system,
+	window,
+	widget::{column, container, mouse_area, operation::focus, text},
+	Border, Element, Length, Shadow, Size, Subscription, Task,
 };
-use style::{DARK_PURPLE, DISABLED_TEXT_COLOR, LIGHT_PURPLE};
+use keymap::{Action, KEYMAP};
+use style::{font_scale, theme};
 
 enum CurrentView {
+	Confirmation,
 	ProgramList,
 	SudoPassword,
 }
 
-#[derive(Debug)]
+type Launch = (String, Option<String>, autocomplete::CommandType);
+
+#[derive(Clone, Debug)]
 enum Message {
-	KeyPressed(keyboard::Event),
+	Action(Action),
+	ActionOnText(Action), // the text input has already used the key, see keymap::edits_text
+	ConfirmationViewMessage(confirmation::Message),
+	DismissErrors,
 	ProgramsListMessage(programs_list::Message),
 	SudoPasswordViewMessage(sudo_password::Message),
+	SystemThemeChanged(iced::theme::Mode),
 }
 
 struct Window {
+	confirmation_view: confirmation::View,
 	current_view: CurrentView,
+	errors: Vec<Error>, // shown above the current view until they are clicked away
+	layer_shell: bool, // the surface is resized by the layer_shell module instead
+	pending_launch: Option<Launch>, // waiting for the confirmation view
 	programs_list: programs_list::View,
 	sudo_command: (Option<String>, Option<String>),
 	sudo_password_view: sudo_password::View,
+	window_height: f32, // what the window was last resized to
 }
 
 impl Window {
 	fn boot() -> (Self, Task<Message>) {
+		Window::start(false)
+	}
+
+	fn start(layer_shell: bool) -> (Self, Task<Message>) {
 		let programs_list = programs_list::View::new();
 		let text_input_id = programs_list.text_input.clone();
 
-		(
-			Window {
-				current_view: CurrentView::ProgramList,
-				programs_list,
-				sudo_command: (None, None),
-				sudo_password_view: sudo_password::View::new(),
-			},
+		// the config is loaded piece by piece on first use, everything wrong with it should be
+		// shown right away
+		style::load_themes();
+		lazy_static::initialize(&KEYMAP);
+
+		let mut state = Window {
+			confirmation_view: confirmation::View::new(),
+			current_view: CurrentView::ProgramList,
+			errors: error::take_reported(),
+			layer_shell,
+			pending_launch: None,
+			programs_list,
+			sudo_command: (None, None),
+			sudo_password_view: sudo_password::View::new(),
+			window_height: CONFIG.window.height,
+		};
+
+		let mut tasks = vec![
 			focus(text_input_id),
-		)
+			state
+				.programs_list
+				.load_windows()
+				.map(Message::ProgramsListMessage),
+		];
+
+		// the system preference is only known once the window is up
+		if CONFIG.theme.follow_system {
+			tasks.push(system::theme().map(Message::SystemThemeChanged));
+		}
+
+		if CONFIG.window.auto_fit {
+			tasks.push(state.fit_window());
+		}
+
+		// reading aliases starts the user's shell with all of its rc files, the list makes do with
+		// the outdated ones until the next start
+		if shell::needs_refresh() {
+			tasks.push(
+				Task::perform(
+					tokio::task::spawn_blocking(shell::refresh_shell_entries),
+					|_| (),
+				)
+				.discard(),
+			);
+		}
+
+		(state, Task::batch(tasks))
 	}
 
 	fn subscription(&self) -> Subscription<Message> {
-		keyboard::listen().map(|event| Message::KeyPressed(event))
+		// the focused text input captures most keys, like enter or escape, without doing anything
+		// with them, so captured events are looked at as well
+		let keys = event::listen_with(|event, status, _| match event {
+			iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+				let action = KEYMAP.action(&key, modifiers)?;
+				if status == event::Status::Captured && keymap::edits_text(&key, modifiers) {
+					Some(Message::ActionOnText(action))
+				} else {
+					Some(Message::Action(action))
+				}
+			}
+			_ => None,
+		});
+
+		if CONFIG.theme.follow_system {
+			Subscription::batch([keys, system::theme_changes().map(Message::SystemThemeChanged)])
+		} else {
+			keys
+		}
 	}
 
-	fn update(&mut self, message: Message) -> Task<Message> {
-		match message {
-			Message::KeyPressed(key) => match key {
-				keyboard::Event::KeyPressed {
-					key: Key::Named(Named::ArrowDown),
-					..
-				} => self
-					.programs_list
-					.update(programs_list::Message::SelectUp)
-					.map(move |message| Message::ProgramsListMessage(message)),
-				keyboard::Event::KeyPressed {
-					key: Key::Named(Named::Enter),
-					..
-				} => {
-					if let CurrentView::ProgramList = self.current_view {
-						match self.programs_list.start_program() {
-							(command, base_command, autocomplete::CommandType::Normal)
-							| (command, base_command, autocomplete::CommandType::OpenProject) => {
-								launcher::launch_program(command, base_command);
-								Task::none()
-							}
-							(command, base_command, autocomplete::CommandType::Sudo) => {
-								self.current_view = CurrentView::SudoPassword;
-								self.sudo_command = (Some(command), base_command);
-								focus(self.sudo_password_view.text_input.clone())
-							}
-						}
-					} else {
-						let command = self.sudo_command.0.as_ref();
-						let base_command = self.sudo_command.1.clone();
-						launcher::launch_program_sudo(
-							command.unwrap().clone(),
-							base_command,
-							self.sudo_password_view.get_password(),
-						);
-
-						Task::none()
-					}
+	fn launch(&mut self, launch: Launch) -> Task<Message> {
+		// nothing is selected
+		if launch.0.is_empty() {
+			return Task::none();
+		}
+
+		self.errors.clear();
+		match launch {
+			(command, base_command, autocomplete::CommandType::Normal) => {
+				self.finish(launcher::launch_program(command, base_command))
+			}
+			(path, base_command, autocomplete::CommandType::OpenProject(action)) => {
+				self.finish(launcher::launch_project(path, base_command, action))
+			}
+			(label, _, autocomplete::CommandType::Custom) => {
+				self.finish(launcher::launch_custom(label))
+			}
+			(id, _, autocomplete::CommandType::FocusWindow) => {
+				self.finish(launcher::focus_window(id))
+			}
+			(value, _, autocomplete::CommandType::Calculation) => {
+				calculator::write_ans(&value);
+				copy_to_clipboard(value)
+			}
+			(id, _, autocomplete::CommandType::ClipboardEntry) => {
+				copy_to_clipboard(clipboard_history::entry_text(&id).unwrap_or_default())
+			}
+			(_, _, autocomplete::CommandType::Session(action)) => {
+				self.finish(launcher::run_session_action(action))
+			}
+			(symbol, _, autocomplete::CommandType::Symbol) => self.insert_symbol(symbol),
+			(command, base_command, autocomplete::CommandType::Sudo) => {
+				self.current_view = CurrentView::SudoPassword;
+				self.sudo_command = (Some(command), base_command);
+				focus(self.sudo_password_view.text_input.clone())
+			}
+			(name, _, autocomplete::CommandType::Unit(action, scope)) => {
+				// changing system units needs root, so those go through the password prompt
+				if action.is_privileged() && scope == autocomplete::UnitScope::System {
+					let command = format!("sudo {}", systemd::command(&name, scope, action));
+					return self.launch((command, None, autocomplete::CommandType::Sudo));
+				}
+
+				self.finish(launcher::run_unit_action(name, scope, action))
+			}
+		}
+	}
+
+	// the finder only goes away once something was launched, failures are shown in it instead
+	fn finish(&mut self, result: Result<(), Error>) -> Task<Message> {
+		if let Err(error) = result {
+			self.errors.push(error);
+			self.current_view = CurrentView::ProgramList;
+			return focus(self.programs_list.text_input.clone());
+		}
+
+		// bookkeeping that failed after a launch has no window left to be shown in
+		for error in error::take_reported() {
+			eprintln!("{}", error);
+		}
+
+		std::process::exit(0);
+	}
+
+	// symbols are counted first, once they are copied or typed the finder is gone
+	fn insert_symbol(&mut self, symbol: String) -> Task<Message> {
+		launcher::update_symbol_frequency(&symbol).unwrap_or_else(error::reported);
+
+		match CONFIG.symbols.action {
+			SymbolAction::Copy => copy_to_clipboard(symbol),
+			SymbolAction::Type => self.finish(launcher::type_text(symbol)),
+		}
+	}
+
+	// launches the selection, or asks about it first when it is something that cannot be undone
+	fn start_selected(&mut self) -> Task<Message> {
+		let launch = self.programs_list.start_program();
+		if let Some(command) = confirmation_command(&launch) {
+			self.current_view = CurrentView::Confirmation;
+			self.confirmation_view.ask(command);
+			self.pending_launch = Some(launch);
+			focus(self.confirmation_view.text_input.clone())
+		} else {
+			self.launch(launch)
+		}
+	}
+
+	// copies what enter would act on instead of acting on it, e.g. the command of a program entry
+	fn copy_selected(&self) -> Task<Message> {
+		if !matches!(self.current_view, CurrentView::ProgramList) {
+			return Task::none();
+		}
+
+		match self.programs_list.start_program() {
+			(_, _, autocomplete::CommandType::FocusWindow) => {
+				copy_to_clipboard(self.programs_list.selected_text())
+			}
+			(label, _, autocomplete::CommandType::Custom) => copy_to_clipboard(
+				find_command(&label)
+					.map(|command| command.command.clone())
+					.unwrap_or_default(),
+			),
+			(id, _, autocomplete::CommandType::ClipboardEntry) => {
+				copy_to_clipboard(clipboard_history::entry_text(&id).unwrap_or_default())
+			}
+			(_, _, autocomplete::CommandType::Session(action)) => {
+				copy_to_clipboard(CONFIG.session.command(action))
+			}
+			(value, _, _) => copy_to_clipboard(value),
+		}
+	}
+
+	fn perform(&mut self, action: Action) -> Task<Message> {
+		let list_message = match action {
+			Action::Next => programs_list::Message::SelectNext,
+			Action::Prev => programs_list::Message::SelectPrevious,
+			Action::PageDown => programs_list::Message::SelectPageDown,
+			Action::PageUp => programs_list::Message::SelectPageUp,
+			Action::First => programs_list::Message::SelectFirst,
+			Action::Last => programs_list::Message::SelectLast,
+			Action::Complete => programs_list::Message::Autocomplete,
+			Action::AcceptAlt => programs_list::Message::ShowActions,
+			Action::DeleteHistoryEntry => programs_list::Message::DeleteSelected,
+			Action::Accept => return self.accept(),
+			Action::RunInTerminal => return self.run_selected_in_terminal(),
+			Action::Copy => return self.copy_selected(),
+			Action::Cancel => {
+				// backing out of a confirmation returns to the list instead of closing
+				if let CurrentView::Confirmation = self.current_view {
+					self.current_view = CurrentView::ProgramList;
+					self.pending_launch = None;
+					return focus(self.programs_list.text_input.clone());
+				}
+
+				// closing after a failed launch still counts as one
+				std::process::exit(self.errors.first().map_or(0, Error::exit_code));
+			}
+		};
+
+		self.programs_list
+			.update(list_message)
+			.map(Message::ProgramsListMessage)
+	}
+
+	fn accept(&mut self) -> Task<Message> {
+		match self.current_view {
+			CurrentView::ProgramList => self.start_selected(),
+			CurrentView::Confirmation => {
+				if !self.confirmation_view.is_confirmed() {
+					return Task::none();
 				}
-				keyboard::Event::KeyPressed {
-					key: Key::Named(Named::Escape),
-					..
-				} => {
-					std::process::exit(0);
+
+				self.current_view = CurrentView::ProgramList;
+				if let Some(launch) = self.pending_launch.take() {
+					self.launch(launch)
+				} else {
+					Task::none()
 				}
-				keyboard::Event::KeyPressed {
-					key: Key::Named(Named::Tab),
-					..
-				} => self
-					.programs_list
-					.update(programs_list::Message::Autocomplete)
-					.map(move |message| Message::ProgramsListMessage(message)),
-				keyboard::Event::KeyPressed {
-					key: Key::Named(Named::ArrowUp),
-					..
-				} => self
-					.programs_list
-					.update(programs_list::Message::SelectDown)
-					.map(move |message| Message::ProgramsListMessage(message)),
-				_ => Task::none(),
-			},
+			}
+			CurrentView::SudoPassword => {
+				let command = self.sudo_command.0.as_ref();
+				let base_command = self.sudo_command.1.clone();
+				let result = launcher::launch_program_sudo(
+					command.unwrap().clone(),
+					base_command,
+					self.sudo_password_view.get_password(),
+				);
+
+				self.finish(result)
+			}
+		}
+	}
+
+	// only plain commands make sense in a terminal, everything else is launched the usual way
+	fn run_selected_in_terminal(&mut self) -> Task<Message> {
+		if !matches!(self.current_view, CurrentView::ProgramList) {
+			return Task::none();
+		}
+
+		let launch = self.programs_list.start_program();
+		if let (command, base_command, autocomplete::CommandType::Normal) = &launch
+			&& confirmation_command(&launch).is_none()
+		{
+			if command.is_empty() {
+				return Task::none();
+			}
+
+			self.errors.clear();
+			let result = launcher::launch_in_terminal(command.clone(), base_command.clone());
+			return self.finish(result);
+		}
+
+		self.start_selected()
+	}
+
+	fn update(&mut self, message: Message) -> Task<Message> {
+		let task = match message {
+			Message::Action(action) => self.perform(action),
+			// ctrl+c copies text selected in the input, and the highlighted row once there is one
+			Message::ActionOnText(Action::Copy) if self.programs_list.has_highlighted_row() => {
+				self.perform(Action::Copy)
+			}
+			Message::ActionOnText(_) => Task::none(),
+			Message::ProgramsListMessage(programs_list::Message::Activate) => {
+				if let CurrentView::ProgramList = self.current_view {
+					self.start_selected()
+				} else {
+					Task::none()
+				}
+			}
 			Message::ProgramsListMessage(message) => self
 				.programs_list
 				.update(message)
 				.map(move |message| Message::ProgramsListMessage(message)),
+			Message::ConfirmationViewMessage(message) => self
+				.confirmation_view
+				.update(message)
+				.map(Message::ConfirmationViewMessage),
 			Message::SudoPasswordViewMessage(message) => self
 				.sudo_password_view
 				.update(message)
 				.map(move |message| Message::SudoPasswordViewMessage(message)),
+			Message::SystemThemeChanged(mode) => {
+				style::set_system_mode(mode);
+				Task::none()
+			}
+			Message::DismissErrors => {
+				self.errors.clear();
+				Task::none()
+			}
+		};
+
+		// e.g. a frequency file that could not be read when a mode was entered
+		self.errors.extend(error::take_reported());
+
+		if CONFIG.window.auto_fit {
+			Task::batch([task, self.fit_window()])
+		} else {
+			task
+		}
+	}
+
+	// shrinks the window to the rows there are, it never grows past the configured height
+	fn fit_window(&mut self) -> Task<Message> {
+		// errors can be any number of lines long, they get all the room there is
+		let height = if let CurrentView::ProgramList = self.current_view
+			&& self.errors.is_empty()
+		{
+			let border = 2.0 * (1 + CONFIG.window.padding) as f32;
+			let content = self.programs_list.fitted_height() + border;
+			(content * CONFIG.window.scale).min(CONFIG.window.height)
+		} else {
+			CONFIG.window.height
+		};
+
+		if height == self.window_height {
+			return Task::none();
 		}
+
+		self.window_height = height;
+		if self.layer_shell {
+			return Task::none();
+		}
+
+		window::latest()
+			.and_then(move |id| window::resize(id, Size::new(CONFIG.window.width, height)))
 	}
 
 	fn view(&self) -> Element<'_, Message> {
-		match self.current_view {
-			CurrentView::ProgramList => container(
-				self.programs_list
-					.view()
-					.map(move |message| Message::ProgramsListMessage(message)),
-			)
-			.height(Length::Fill)
-			.padding(1)
-			.style(|_| container::Style {
-				text_color: Some(DISABLED_TEXT_COLOR),
-				background: Some(DARK_PURPLE.into()),
-				border: Border::default().width(1.0).color(LIGHT_PURPLE),
-				shadow: Shadow::default(),
-				snap: false,
-			})
-			.into(),
-			CurrentView::SudoPassword => container(
-				self.sudo_password_view
-					.view()
-					.map(move |message| Message::SudoPasswordViewMessage(message)),
-			)
+		let theme = theme();
+		let view = match self.current_view {
+			CurrentView::ProgramList => self
+				.programs_list
+				.view()
+				.map(move |message| Message::ProgramsListMessage(message)),
+			CurrentView::Confirmation => self
+				.confirmation_view
+				.view()
+				.map(Message::ConfirmationViewMessage),
+			CurrentView::SudoPassword => self
+				.sudo_password_view
+				.view()
+				.map(move |message| Message::SudoPasswordViewMessage(message)),
+		};
+
+		let view = if self.errors.is_empty() {
+			view
+		} else {
+			column![self.error_banner(), view].into()
+		};
+
+		container(view)
 			.height(Length::Fill)
-			.padding(1)
-			.style(|_| container::Style {
-				text_color: Some(DISABLED_TEXT_COLOR),
-				background: Some(DARK_PURPLE.into()),
-				border: Border::default().width(1.0).color(LIGHT_PURPLE),
+			.padding(1 + CONFIG.window.padding)
+			.style(move |_| container::Style {
+				text_color: Some(theme.text),
+				background: Some(theme.background.into()),
+				border: Border::default().width(1.0).color(theme.border),
 				shadow: Shadow::default(),
 				snap: false,
 			})
-			.into(),
+			.into()
+	}
+
+	fn error_banner(&self) -> Element<'_, Message> {
+		let theme = theme();
+		let lines = self.errors.iter().map(|error| {
+			text(error.to_string())
+				.size(10.0 * font_scale())
+				.width(Length::Fill)
+				.into()
+		});
+
+		mouse_area(
+			container(column(lines).spacing(2))
+				.width(Length::Fill)
+				.padding([4, 7])
+				.style(move |_| container::Style {
+					text_color: Some(theme.background),
+					background: Some(theme.error.into()),
+					border: Border::default(),
+					shadow: Shadow::default(),
+					snap: false,
+				}),
+		)
+		.interaction(mouse::Interaction::Pointer)
+		.on_press(Message::DismissErrors)
+		.into()
+	}
+}
+
+// the exact command that is about to run, for launches that have to be confirmed first
+fn confirmation_command(launch: &Launch) -> Option<String> {
+	let is_dangerous = |command: &str| {
+		let program = command.split_whitespace().next().unwrap_or_default();
+		program == "killall"
+			|| CONFIG
+				.confirmation
+				.commands
+				.iter()
+				.any(|dangerous| dangerous == program)
+	};
+
+	match launch {
+		(command, _, autocomplete::CommandType::Normal) if is_dangerous(command) => {
+			Some(shell::expand_command(command))
+		}
+		(_, _, autocomplete::CommandType::Session(action)) => Some(CONFIG.session.command(*action)),
+		(command, _, autocomplete::CommandType::Sudo) => Some(command.clone()),
+		(label, _, autocomplete::CommandType::Custom) => {
+			let custom = find_command(label)?;
+			if custom.confirm || is_dangerous(&custom.command) {
+				Some(custom.command.clone())
+			} else {
+				None
+			}
 		}
+		_ => None,
 	}
 }
 
+// wl-copy and xclip come before iced's own clipboard on purpose: we exit right after copying, and
+// on wayland and x11 the selection is served by whoever owns it, so whatever iced put there would
+// be gone the moment the window closes
+fn copy_to_clipboard(value: String) -> Task<Message> {
+	if value.is_empty() {
+		return Task::none();
+	}
+
+	// without wl-copy or xclip only the window itself can serve the clipboard, so it stays open
+	if let Err(error) = clipboard::copy(&value) {
+		error::report(Error::Launch(String::from("the clipboard program"), error));
+		return iced::clipboard::write(value);
+	}
+
+	std::process::exit(0);
+}
+
 fn main() {
 	env_logger::init();
 
+	// the clipboard watcher runs in the background and must not count as an open finder
+	match std::env::args().nth(1).as_deref() {
+		Some("--watch-clipboard") => return clipboard_history::watch(),
+		Some("--store-clipboard") => return clipboard_history::store_from_stdin(),
+		_ => {}
+	}
+
 	// only open one finder at a time
 	let pgrep_out = String::from_utf8(
 		std::process::Command::new("pgrep")
@@ -174,25 +542,33 @@ fn main() {
 	)
 	.unwrap();
 
-	if pgrep_out.trim().find('\n').is_none() {
-		iced::application(Window::boot, Window::update, Window::view)
-			.font(include_bytes!("../fonts/NotoSans-Regular.ttf"))
-			.default_font(Font {
-				family: Family::Name("Noto Sans"),
-				weight: Weight::Medium,
-				stretch: Stretch::Normal,
-				style: Style::Normal,
-			})
-			.window_size((300.0, 200.0))
-			.scale_factor(|_| 1.5)
+	if pgrep_out.trim().find('\n').is_some() {
+		println!("already open");
+	} else if CONFIG.layer_shell.enabled && layer_shell::is_supported() {
+		if let Err(error) = layer_shell::run() {
+			eprintln!("Could not open bansheefinder3 {:?}", error);
+			std::process::exit(1);
+		}
+	} else {
+		let mut application = iced::application(Window::boot, Window::update, Window::view)
+			.font(include_bytes!("../fonts/MaterialIcons-Regular.ttf"))
+			.default_font(style::font())
+			.window_size((CONFIG.window.width, CONFIG.window.height))
+			.scale_factor(|_| CONFIG.window.scale)
 			.decorations(false)
 			.resizable(false)
-			.antialiasing(false)
+			.antialiasing(CONFIG.window.antialiasing)
 			.subscription(Window::subscription)
-			.title("bansheefinder3")
-			.run()
-			.expect("Could not open bansheefinder3");
-	} else {
-		println!("already open");
+			.title("bansheefinder3");
+
+		if let Some(font) = style::bundled_font() {
+			application = application.font(font);
+		}
+
+		// there is no window to show this in
+		if let Err(error) = application.run() {
+			eprintln!("Could not open bansheefinder3 {:?}", error);
+			std::process::exit(1);
+		}
 	}
 }
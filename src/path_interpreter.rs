@@ -1,22 +1,13 @@
 use chrono::Local;
-use lazy_static::lazy_static;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::Read;
 use std::io::Write;
-use std::path::Path;
-
-lazy_static! {
-	static ref IGNORED_PROGRAMS: HashSet<String> = {
-		let mut set = HashSet::new();
-		set.insert(String::from("restart"));
-		set.insert(String::from("off"));
-		set.insert(String::from("hibernate"));
-		set.insert(String::from("rest"));
-		return set;
-	};
-}
+use std::path::{Path, PathBuf};
+
+use crate::config::{create_data_directory, data_directory, expand_home, ProjectsConfig, CONFIG};
+use crate::error::Error;
 
 #[derive(Default, Eq, PartialEq)]
 pub struct ProgramFrequency {
@@ -76,21 +67,32 @@ impl PartialOrd for ProgramFrequency {
 	}
 }
 
-pub fn read_command_frequency() -> ProgramFrequencyMap {
-	let file = OpenOptions::new()
-		.read(true)
-		.open("/home/me/.local/share/bansheefinder/frequency.map");
+pub fn read_command_frequency() -> Result<ProgramFrequencyMap, Error> {
+	read_frequency(&data_directory().join("frequency.map"))
+}
+
+// projects are kept in their own file so program lists never see them
+pub fn read_project_frequency() -> Result<ProgramFrequencyMap, Error> {
+	read_frequency(&data_directory().join("projects.map"))
+}
+
+pub fn read_symbol_frequency() -> Result<ProgramFrequencyMap, Error> {
+	read_frequency(&data_directory().join("symbols.map"))
+}
+
+// a file that cannot be read is an error instead of an empty map, so it never gets overwritten
+fn read_frequency(path: &Path) -> Result<ProgramFrequencyMap, Error> {
+	let file = OpenOptions::new().read(true).open(path);
 
 	let mut file = if let Err(_) = file {
-		return ProgramFrequencyMap::default();
+		return Ok(ProgramFrequencyMap::default());
 	} else {
 		file.unwrap()
 	};
 
 	let mut contents = Vec::<u8>::new();
 	if let Err(error) = file.read_to_end(&mut contents) {
-		eprintln!("Could not read file {:?}", error);
-		std::process::exit(0); // terminate program right away so we don't overwrite data
+		return Err(Error::Read(path.to_path_buf(), error));
 	}
 
 	let mut map = HashMap::new();
@@ -121,52 +123,55 @@ pub fn read_command_frequency() -> ProgramFrequencyMap {
 		map.insert(string, ProgramFrequency { count, timestamp });
 	}
 
-	return ProgramFrequencyMap { max, map };
+	return Ok(ProgramFrequencyMap { max, map });
 }
 
-fn handle_write(file: &mut File, buffer: &[u8]) -> bool {
-	if let Err(error) = file.write(buffer) {
-		eprintln!("Could not write to file {:?})", error);
-		return false;
-	} else {
-		return true;
-	}
+fn handle_write(file: &mut File, path: &Path, buffer: &[u8]) -> Result<(), Error> {
+	file.write_all(buffer)
+		.map_err(|error| Error::Write(path.to_path_buf(), error))
 }
 
-pub fn write_command_frequency(map: ProgramFrequencyMap) {
-	let file = OpenOptions::new()
-		.write(true)
-		.create(true)
-		.open("/home/me/.local/share/bansheefinder/frequency.map");
+pub fn write_command_frequency(map: ProgramFrequencyMap) -> Result<(), Error> {
+	write_frequency(&data_directory().join("frequency.map"), map)
+}
 
-	let mut file = if let Err(error) = file {
-		eprintln!("Could not write command freq file {:?}", error);
-		return;
-	} else {
-		file.unwrap()
-	};
+pub fn write_project_frequency(map: ProgramFrequencyMap) -> Result<(), Error> {
+	write_frequency(&data_directory().join("projects.map"), map)
+}
+
+pub fn write_symbol_frequency(map: ProgramFrequencyMap) -> Result<(), Error> {
+	write_frequency(&data_directory().join("symbols.map"), map)
+}
+
+fn write_frequency(path: &Path, map: ProgramFrequencyMap) -> Result<(), Error> {
+	// the maps are rewritten whole, a shorter one must not leave the end of the old file behind
+	let mut file = create_data_directory()
+		.and_then(|_| {
+			OpenOptions::new()
+				.write(true)
+				.create(true)
+				.truncate(true)
+				.open(path)
+		})
+		.map_err(|error| Error::Write(path.to_path_buf(), error))?;
 
 	for (key, value) in map.map {
-		if key.len() > 255 || IGNORED_PROGRAMS.contains(&key) {
-			// TODO fix this from happening in a nice way
+		if key.len() > 255 {
 			continue;
 		}
 
-		if !handle_write(&mut file, &[key.len() as u8])
-			|| !handle_write(&mut file, key.as_bytes())
-			|| !handle_write(&mut file, &[(value.count & 0xFF) as u8])
-			|| !handle_write(&mut file, &[((value.count & 0xFF00) >> 8) as u8])
-		{
-			return;
-		}
+		handle_write(&mut file, path, &[key.len() as u8])?;
+		handle_write(&mut file, path, key.as_bytes())?;
+		handle_write(&mut file, path, &[(value.count & 0xFF) as u8])?;
+		handle_write(&mut file, path, &[((value.count & 0xFF00) >> 8) as u8])?;
 
 		// write timestamp
 		for i in 0..8 {
-			if !handle_write(&mut file, &[(value.timestamp >> (i * 8)) as u8 & 0xFF]) {
-				return;
-			}
+			handle_write(&mut file, path, &[(value.timestamp >> (i * 8)) as u8 & 0xFF])?;
 		}
 	}
+
+	Ok(())
 }
 
 pub fn get_programs() -> Option<Vec<String>> {
@@ -176,8 +181,15 @@ pub fn get_programs() -> Option<Vec<String>> {
 		return None;
 	};
 
+	let script_directories = CONFIG
+		.shell
+		.script_directories
+		.iter()
+		.map(|directory| expand_home(directory).to_string_lossy().to_string())
+		.collect::<Vec<String>>();
+
 	let mut output = Vec::new();
-	for directory in paths.split(':') {
+	for directory in paths.split(':').chain(script_directories.iter().map(String::as_str)) {
 		let path = Path::new(directory);
 		if let Ok(read_directory) = std::fs::read_dir(path) {
 			for program in read_directory {
@@ -187,15 +199,166 @@ pub fn get_programs() -> Option<Vec<String>> {
 		}
 	}
 
+	// a script directory that is also on $PATH would list everything in it twice
+	output.sort();
+	output.dedup();
 	Some(output)
 }
 
-pub fn get_projects() -> Option<Vec<String>> {
+// the one line summary of the program's man page, or where it lives when it has none
+pub fn describe_program(program: &str) -> Option<String> {
+	let whatis = std::process::Command::new("whatis")
+		.arg(program)
+		.stderr(std::process::Stdio::null())
+		.output();
+
+	if let Ok(output) = whatis
+		&& output.status.success()
+		&& let Some((_, summary)) = String::from_utf8_lossy(&output.stdout)
+			.lines()
+			.next()
+			.and_then(|line| line.split_once(" - "))
+	{
+		return Some(summary.trim().to_string());
+	}
+
+	std::env::var("PATH")
+		.ok()?
+		.split(':')
+		.map(|directory| Path::new(directory).join(program))
+		.find(|path| path.is_file())
+		.map(|path| path.to_string_lossy().to_string())
+}
+
+#[derive(Clone, Debug)]
+pub struct Project {
+	pub name: String, // path relative to the root it was found in
+	pub path: PathBuf,
+}
+
+fn is_project(directory: &Path, markers: &[String]) -> bool {
+	markers.iter().any(|marker| directory.join(marker).exists())
+}
+
+fn find_projects(
+	root: &Path,
+	directory: &Path,
+	depth: usize,
+	config: &ProjectsConfig,
+	output: &mut Vec<Project>,
+) {
+	let read_directory = if let Ok(read_directory) = std::fs::read_dir(directory) {
+		read_directory
+	} else {
+		return;
+	};
+
+	for entry in read_directory.flatten() {
+		// do not follow symlinks so we cannot loop forever
+		if !entry
+			.file_type()
+			.map(|file_type| file_type.is_dir())
+			.unwrap_or(false)
+		{
+			continue;
+		}
+
+		let file_name = entry.file_name().to_string_lossy().to_string();
+		if file_name.starts_with('.') || config.ignored.contains(&file_name) {
+			continue;
+		}
+
+		let path = entry.path();
+		if is_project(&path, &config.markers) {
+			output.push(Project {
+				name: path
+					.strip_prefix(root)
+					.unwrap()
+					.to_string_lossy()
+					.to_string(),
+				path,
+			});
+		} else if depth < config.max_depth {
+			find_projects(root, &path, depth + 1, config, output);
+		}
+	}
+}
+
+pub fn get_projects() -> Vec<Project> {
+	let config = &CONFIG.projects;
+
 	let mut output = Vec::new();
-	for program in std::fs::read_dir("/home/me/Projects").unwrap() {
-		// read all programs in directory
-		output.push(program.unwrap().file_name().into_string().unwrap());
+	for root in config.roots.iter() {
+		let root = expand_home(root);
+		find_projects(&root, &root, 1, config, &mut output);
 	}
 
-	Some(output)
+	disambiguate_projects(&mut output);
+	output
+}
+
+// projects are looked up by name, so a name found under two roots gets the name of its root in
+// front, e.g. "work/foo" and "personal/foo", and the full path when that is not enough either
+fn disambiguate_projects(projects: &mut [Project]) {
+	let duplicates = |projects: &[Project]| {
+		let mut counts = HashMap::<String, usize>::new();
+		for project in projects.iter() {
+			*counts.entry(project.name.clone()).or_default() += 1;
+		}
+
+		projects
+			.iter()
+			.map(|project| counts[&project.name] > 1)
+			.collect::<Vec<bool>>()
+	};
+
+	let duplicate = duplicates(projects);
+	for (project, duplicate) in projects.iter_mut().zip(duplicate) {
+		let depth = Path::new(&project.name).components().count();
+		let root = project.path.ancestors().nth(depth).and_then(Path::file_name);
+		if let (true, Some(root)) = (duplicate, root) {
+			project.name = format!("{}/{}", root.to_string_lossy(), project.name);
+		}
+	}
+
+	let duplicate = duplicates(projects);
+	for (project, duplicate) in projects.iter_mut().zip(duplicate) {
+		if duplicate {
+			project.name = project.path.to_string_lossy().to_string();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn frequency_map(names: &[&str]) -> ProgramFrequencyMap {
+		let mut frequency = ProgramFrequencyMap::default();
+		for name in names {
+			frequency.map.insert(
+				name.to_string(),
+				ProgramFrequency {
+					count: 3,
+					timestamp: 1_700_000_000,
+				},
+			);
+		}
+
+		frequency
+	}
+
+	#[test]
+	fn rewriting_a_shorter_map_drops_the_old_entries() {
+		let path = std::env::temp_dir().join(format!("bansheefinder-{}.map", std::process::id()));
+		write_frequency(&path, frequency_map(&["firefox", "alacritty", "code"])).unwrap();
+		write_frequency(&path, frequency_map(&["vim"])).unwrap();
+
+		let frequency = read_frequency(&path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		assert_eq!(frequency.map.keys().collect::<Vec<_>>(), ["vim"]);
+		assert_eq!(frequency.map["vim"].count, 3);
+		assert_eq!(frequency.map["vim"].timestamp, 1_700_000_000);
+	}
 }
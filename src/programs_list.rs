@@ -1,39 +1,97 @@
 use iced::alignment::Horizontal;
 use iced::border::radius;
-use iced::widget::operation::move_cursor_to_end;
-use iced::widget::scrollable::{Direction, Rail, Scrollbar};
-use iced::widget::{self, column, container, scrollable, text, text_input};
+use iced::mouse::Interaction;
+use iced::widget::operation::{
+	move_cursor_to_end, scroll_to, snap_to, AbsoluteOffset, RelativeOffset,
+};
+use iced::widget::scrollable::{Direction, Rail, Scrollbar, Viewport};
+use iced::widget::{
+	self, column, container, image, mouse_area, row, scrollable, svg, text, text_input,
+};
+use iced::widget::text::{Shaping, Wrapping};
 use iced::{Alignment, Border, Color, Element, Length, Shadow, Task};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 use crate::autocomplete::default::DefaultFactory;
+use crate::autocomplete::window_switcher::{get_window_entries, WindowEntry};
 use crate::autocomplete::{CommandType, Factory, State};
-use crate::style::{
-	DARK_PURPLE, DISABLED_TEXT_COLOR, LIGHT_PURPLE, SCROLLBAR_PURPLE, SELECTED_TEXT_COLOR,
-	TEXT_COLOR,
-};
+use crate::config::CONFIG;
+use crate::icons;
+use crate::style::{font_scale, theme, ICON_FONT};
 
 #[derive(Clone, Debug)]
 pub enum Message {
+	Activate, // handled by the window, which launches the selection the same way enter does
 	Autocomplete,
-	SelectUp,
-	SelectDown,
+	CommitTimesLoaded(HashMap<String, u64>),
+	DeleteSelected,
+	IconsLoaded(Vec<(String, Option<PathBuf>)>),
+	RowClicked(usize),
+	RowEntered(usize),
+	RowExited(usize),
+	Scrolled(Viewport),
+	SelectFirst,
+	SelectLast,
+	SelectPageUp,
+	SelectPageDown,
+	SelectNext,
+	SelectPrevious,
+	ShowActions,
+	SubtitleLoaded(String, Option<String>),
 	Typed(String),
+	WindowsLoaded(Vec<WindowEntry>),
+}
+
+// rows have a fixed height so the position of the selected one can be worked out for scrolling,
+// these and the sizes below are for the default font size and scaled with it
+const ROW_HEIGHT: f32 = 22.0;
+const TWO_LINE_ROW_HEIGHT: f32 = 28.0;
+
+const ICON_SIZE: f32 = 12.0;
+const SUBTITLE_SIZE: f32 = 7.0;
+const INPUT_SIZE: f32 = 15.0;
+const INPUT_PADDING: f32 = 7.0;
+
+enum Icon {
+	Image(image::Handle),
+	Svg(svg::Handle),
+}
+
+impl Icon {
+	fn from_path(path: PathBuf) -> Self {
+		if path.extension().is_some_and(|extension| extension == "svg") {
+			Icon::Svg(svg::Handle::from_path(path))
+		} else {
+			Icon::Image(image::Handle::from_path(path))
+		}
+	}
 }
 
 pub struct View {
+	hovered: Option<usize>,
+	icons: HashMap<String, Option<Icon>>, // by icon name, contains an entry once it is requested
+	scroll_offset: f32,
+	scrollable: widget::Id,
 	search: String,
 	selected: Option<String>,
 	state: Box<dyn State>,
 	pub text_input: widget::Id,
+	viewport_height: Option<f32>, // only known once the list is long enough to scroll
 }
 
 impl View {
 	pub fn new() -> Self {
 		View {
+			hovered: None,
+			icons: HashMap::new(),
+			scroll_offset: 0.0,
+			scrollable: widget::Id::unique(),
 			search: String::new(),
 			selected: None,
 			state: DefaultFactory::default().create(),
 			text_input: widget::Id::unique(),
+			viewport_height: None,
 		}
 	}
 
@@ -41,119 +99,509 @@ impl View {
 		self.state.get_command()
 	}
 
+	pub fn has_highlighted_row(&self) -> bool {
+		self.selected.is_some()
+	}
+
+	// the highlighted row, or what was typed when nothing is highlighted
+	pub fn selected_text(&self) -> String {
+		self.selected.clone().unwrap_or(self.search.clone())
+	}
+
+	fn row_height(&self, item: &String) -> f32 {
+		let height = if self.state.get_subtitle(item).is_some() {
+			TWO_LINE_ROW_HEIGHT
+		} else {
+			ROW_HEIGHT
+		};
+
+		height * font_scale()
+	}
+
+	// how high the input and the first rows are together, for fitting the window around them
+	pub fn fitted_height(&self) -> f32 {
+		let rows = self.state.get_ui_list().as_ref().map_or(0.0, |list| {
+			list.iter()
+				.take(CONFIG.window.max_rows)
+				.map(|item| self.row_height(item))
+				.sum()
+		});
+
+		// text inputs are as high as a line of text at the default line height plus their padding
+		INPUT_SIZE * font_scale() * 1.3 + INPUT_PADDING * 2.0 + rows
+	}
+
+	// the rows that are on screen, or the first few when we do not know how high the list is yet
+	fn visible_items(&self) -> Vec<String> {
+		let list = if let Some(list) = self.state.get_ui_list() {
+			list
+		} else {
+			return Vec::new();
+		};
+
+		// before the list has been laid out, as many rows as could fit get loaded
+		let height = if let Some(height) = self.viewport_height {
+			height
+		} else {
+			return list.iter().take(CONFIG.window.max_rows).cloned().collect();
+		};
+
+		let mut top = 0.0;
+		let mut output = Vec::new();
+		for item in list.iter() {
+			let bottom = top + self.row_height(item);
+			if bottom > self.scroll_offset {
+				output.push(item.clone());
+			}
+
+			if bottom >= self.scroll_offset + height {
+				break;
+			}
+
+			top = bottom;
+		}
+
+		output
+	}
+
+	fn selected_index(&self) -> Option<usize> {
+		let selected = self.selected.as_ref()?;
+		self.state
+			.get_ui_list()
+			.as_ref()?
+			.iter()
+			.position(|item| item == selected)
+	}
+
+	fn set_selection(&mut self, (search_text, selected_item): (String, Option<String>)) {
+		self.selected = Some(selected_item.unwrap_or(search_text.clone()));
+		self.search = search_text;
+	}
+
+	fn select_index(&mut self, target: usize) {
+		let length = self
+			.state
+			.get_ui_list()
+			.as_ref()
+			.map_or(0, |list| list.len());
+		if length == 0 {
+			return;
+		}
+
+		let selection = self.state.select_index(target.min(length - 1));
+		self.set_selection(selection);
+	}
+
+	fn page_length(&self) -> usize {
+		self.viewport_height
+			.map_or(CONFIG.window.max_rows, |height| {
+				(height / (ROW_HEIGHT * font_scale())) as usize
+			})
+			.max(1)
+	}
+
+	// scrolls just far enough for the selected row to be fully on screen
+	fn scroll_to_selected(&self) -> Task<Message> {
+		let (index, height) =
+			if let (Some(index), Some(height)) = (self.selected_index(), self.viewport_height) {
+				(index, height)
+			} else {
+				return Task::none();
+			};
+
+		let list = self.state.get_ui_list().as_ref().unwrap();
+		let top = list[..index]
+			.iter()
+			.map(|item| self.row_height(item))
+			.sum::<f32>();
+		let bottom = top + self.row_height(&list[index]);
+
+		if top < self.scroll_offset {
+			scroll_to(self.scrollable.clone(), AbsoluteOffset { x: 0.0, y: top })
+		} else if bottom > self.scroll_offset + height {
+			scroll_to(
+				self.scrollable.clone(),
+				AbsoluteOffset {
+					x: 0.0,
+					y: bottom - height,
+				},
+			)
+		} else {
+			Task::none()
+		}
+	}
+
+	// starts background tasks for the subtitles of the rows that are on screen
+	fn load_subtitles(&mut self) -> Task<Message> {
+		let items = self.visible_items();
+		if items.is_empty() {
+			return Task::none();
+		}
+
+		Task::batch(
+			self.state
+				.subtitle_requests(&items)
+				.into_iter()
+				.map(|(item, loader)| {
+					Task::perform(tokio::task::spawn_blocking(loader), move |subtitle| {
+						Message::SubtitleLoaded(item.clone(), subtitle.ok().flatten())
+					})
+				}),
+		)
+	}
+
+	// resolves the themed icons of the rows that are on screen in one background task, looking
+	// through icon themes means touching a lot of files
+	fn load_icons(&mut self) -> Task<Message> {
+		if !CONFIG.icons.enabled {
+			return Task::none();
+		}
+
+		let mut names = self
+			.visible_items()
+			.iter()
+			.filter_map(|item| self.state.get_icon_name(item))
+			.filter(|name| !self.icons.contains_key(name))
+			.collect::<Vec<String>>();
+
+		names.sort();
+		names.dedup();
+		if names.is_empty() {
+			return Task::none();
+		}
+
+		for name in names.iter() {
+			self.icons.insert(name.clone(), None);
+		}
+
+		Task::perform(
+			tokio::task::spawn_blocking(move || icons::resolve(names)),
+			|icons| Message::IconsLoaded(icons.unwrap_or_default()),
+		)
+	}
+
+	// projects are sorted by their last commit once git has been asked in the background
+	fn load_commit_times(&mut self) -> Task<Message> {
+		if let Some(loader) = self.state.commit_times_request() {
+			Task::perform(tokio::task::spawn_blocking(loader), |commit_times| {
+				Message::CommitTimesLoaded(commit_times.unwrap_or_default())
+			})
+		} else {
+			Task::none()
+		}
+	}
+
+	// open windows are listed with the programs, the compositor is asked for them in the background
+	pub fn load_windows(&self) -> Task<Message> {
+		Task::perform(tokio::task::spawn_blocking(get_window_entries), |windows| {
+			Message::WindowsLoaded(windows.unwrap_or_default())
+		})
+	}
+
 	pub fn update(&mut self, message: Message) -> Task<Message> {
+		let task = self.handle_message(message);
+		Task::batch([
+			task,
+			self.load_subtitles(),
+			self.load_icons(),
+			self.load_commit_times(),
+		])
+	}
+
+	fn handle_message(&mut self, message: Message) -> Task<Message> {
 		match message {
+			Message::Activate => Task::none(),
 			Message::Autocomplete => {
-				let (search_text, selected_item) = self.state.autocomplete();
-				let selected_item = if let Some(item) = selected_item {
-					item
-				} else {
-					search_text.clone()
-				};
+				let selection = self.state.autocomplete();
+				self.set_selection(selection);
+				Task::batch([
+					move_cursor_to_end(self.text_input.clone()),
+					self.scroll_to_selected(),
+				])
+			}
+			Message::CommitTimesLoaded(commit_times) => {
+				self.state.set_commit_times(commit_times);
+				self.hovered = None;
+				Task::none()
+			}
+			Message::DeleteSelected => {
+				if let Some((search_text, _)) = self.state.delete_selected() {
+					self.search = search_text;
+					self.selected = None;
+				}
 
-				self.search = search_text;
-				self.selected = Some(selected_item);
 				move_cursor_to_end(self.text_input.clone())
 			}
-			Message::SelectUp => {
-				let (search_text, selected_item) = self.state.select_up();
-				let selected_item = if let Some(item) = selected_item {
-					item
-				} else {
-					search_text.clone()
-				};
+			Message::IconsLoaded(icons) => {
+				for (name, path) in icons {
+					self.icons.insert(name, path.map(Icon::from_path));
+				}
 
-				self.search = search_text;
-				self.selected = Some(selected_item);
-				move_cursor_to_end(self.text_input.clone())
+				Task::none()
 			}
-			Message::SelectDown => {
-				let (search_text, selected_item) = self.state.select_down();
-				let selected_item = if let Some(item) = selected_item {
-					item
-				} else {
-					search_text.clone()
-				};
+			// a double click is a click that selects the row followed by one that launches it
+			Message::RowClicked(index) => {
+				if self.selected_index() == Some(index) {
+					return Task::done(Message::Activate);
+				}
+
+				self.select_index(index);
+				Task::batch([
+					move_cursor_to_end(self.text_input.clone()),
+					self.scroll_to_selected(),
+				])
+			}
+			Message::RowEntered(index) => {
+				self.hovered = Some(index);
+				Task::none()
+			}
+			Message::RowExited(index) => {
+				if self.hovered == Some(index) {
+					self.hovered = None;
+				}
+
+				Task::none()
+			}
+			Message::Scrolled(viewport) => {
+				self.scroll_offset = viewport.absolute_offset().y;
+				self.viewport_height = Some(viewport.bounds().height);
+				Task::none()
+			}
+			Message::SelectFirst => {
+				self.select_index(0);
+				Task::batch([
+					move_cursor_to_end(self.text_input.clone()),
+					self.scroll_to_selected(),
+				])
+			}
+			Message::SelectLast => {
+				self.select_index(usize::MAX);
+				Task::batch([
+					move_cursor_to_end(self.text_input.clone()),
+					self.scroll_to_selected(),
+				])
+			}
+			Message::SelectPageUp => {
+				let index = self.selected_index().unwrap_or(0);
+				self.select_index(index.saturating_sub(self.page_length()));
+				Task::batch([
+					move_cursor_to_end(self.text_input.clone()),
+					self.scroll_to_selected(),
+				])
+			}
+			Message::SelectPageDown => {
+				let index = self
+					.selected_index()
+					.map_or(0, |index| index + self.page_length());
+				self.select_index(index);
+				Task::batch([
+					move_cursor_to_end(self.text_input.clone()),
+					self.scroll_to_selected(),
+				])
+			}
+			Message::SelectNext => {
+				let selection = self.state.select_next();
+				self.set_selection(selection);
+				Task::batch([
+					move_cursor_to_end(self.text_input.clone()),
+					self.scroll_to_selected(),
+				])
+			}
+			Message::SelectPrevious => {
+				let selection = self.state.select_previous();
+				self.set_selection(selection);
+				Task::batch([
+					move_cursor_to_end(self.text_input.clone()),
+					self.scroll_to_selected(),
+				])
+			}
+			Message::ShowActions => {
+				if let Some((search_text, selected_item)) = self.state.show_actions() {
+					self.search = search_text;
+					self.selected = selected_item;
+					self.hovered = None;
+				}
 
-				self.search = search_text;
-				self.selected = Some(selected_item);
 				move_cursor_to_end(self.text_input.clone())
 			}
+			Message::SubtitleLoaded(item, subtitle) => {
+				self.state.set_subtitle(item, subtitle);
+				Task::none()
+			}
+			Message::WindowsLoaded(windows) => {
+				self.state.set_windows(windows);
+				self.hovered = None;
+				Task::none()
+			}
 			Message::Typed(search) => {
 				self.selected = None;
 				self.search = search.clone();
 				self.selected = None;
 				self.state.update_search(search);
-				Task::none()
+
+				// the list starts over, so do the hovered row and the scroll position
+				self.hovered = None;
+				self.scroll_offset = 0.0;
+				snap_to(self.scrollable.clone(), RelativeOffset::START)
 			}
 		}
 	}
 
 	pub fn view(&self) -> Element<'_, Message> {
+		let theme = theme();
+		let icon_size = ICON_SIZE * font_scale();
 		let mut scrollable_column = column![];
 
 		let list = self.state.get_ui_list();
 		if let Some(programs) = list.as_ref() {
-			for autocomplete in programs.iter() {
+			for (index, autocomplete) in programs.iter().enumerate() {
 				let moved_autocomplete = autocomplete.clone();
 
-				scrollable_column = scrollable_column.push(
-					container(
-						text(autocomplete)
+				let mut row = column![text(autocomplete)
+					.align_x(Horizontal::Left)
+					.width(Length::Fill)
+					.size(CONFIG.font.size)];
+
+				// descriptions can be long, rows stay two lines high and cut them off instead
+				if let Some(subtitle) = self.state.get_subtitle(autocomplete) {
+					row = row.push(
+						text(subtitle)
 							.align_x(Horizontal::Left)
 							.width(Length::Fill)
-							.size(9),
-					)
+							.size(SUBTITLE_SIZE * font_scale())
+							.wrapping(Wrapping::None)
+							.color(theme.subtitle),
+					);
+				}
+
+				let themed_icon = self
+					.state
+					.get_icon_name(autocomplete)
+					.and_then(|name| self.icons.get(&name))
+					.and_then(|icon| icon.as_ref());
+
+				// icon names are turned into glyphs by the font's ligatures, which need full shaping
+				let icon: Option<Element<'_, Message>> = match themed_icon {
+					Some(Icon::Image(handle)) => Some(
+						image(handle.clone())
+							.width(icon_size)
+							.height(icon_size)
+							.into(),
+					),
+					Some(Icon::Svg(handle)) => Some(
+						svg(handle.clone())
+							.width(icon_size)
+							.height(icon_size)
+							.into(),
+					),
+					None => self.state.get_icon(autocomplete).map(|icon| {
+						text(icon)
+							.font(ICON_FONT)
+							.shaping(Shaping::Advanced)
+							.size(icon_size)
+							.into()
+					}),
+				};
+
+				let row: Element<'_, Message> = if let Some(icon) = icon {
+					row![icon, row].spacing(4).align_y(Alignment::Center).into()
+				} else {
+					row.into()
+				};
+
+				let row = container(row)
 					.padding(3)
 					.width(Length::Fill)
+					.height(self.row_height(autocomplete))
+					.align_y(Alignment::Center)
 					.style(move |_| {
 						if self.selected.is_some()
 							&& &moved_autocomplete == self.selected.as_ref().unwrap()
 						{
 							container::Style {
-								text_color: Some(TEXT_COLOR),
-								background: Some(LIGHT_PURPLE.into()),
+								text_color: Some(theme.selected_row_text),
+								background: Some(theme.selected_row.into()),
 								border: Border::default(),
 								shadow: Shadow::default(),
 								snap: false,
 							}
 						} else {
 							container::Style {
-								text_color: Some(DISABLED_TEXT_COLOR),
-								background: Some(DARK_PURPLE.into()),
+								text_color: Some(theme.row_text),
+								background: Some(if self.hovered == Some(index) {
+									theme.hover_row.into()
+								} else {
+									theme.row.into()
+								}),
 								border: Border::default(),
 								shadow: Shadow::default(),
 								snap: false,
 							}
 						}
-					}),
+					});
+
+				scrollable_column = scrollable_column.push(
+					mouse_area(row)
+						.interaction(Interaction::Pointer)
+						.on_press(Message::RowClicked(index))
+						.on_enter(Message::RowEntered(index))
+						.on_exit(Message::RowExited(index)),
 				);
 			}
 		}
 
+		let prompt = self.state.get_prompt();
+		let input = text_input(&prompt.placeholder, &self.search)
+			.id(self.text_input.clone())
+			.size(INPUT_SIZE * font_scale())
+			.on_input(Message::Typed)
+			.padding(INPUT_PADDING)
+			.style(move |_, _| text_input::Style {
+				background: theme.input_background.into(),
+				border: Border::default(),
+				icon: Color::BLACK,
+				placeholder: theme.placeholder,
+				value: theme.input_text,
+				selection: theme.highlight,
+			});
+
+		// modes are named in a chip in front of the input, nested ones together like "sudo killall"
+		let search_bar: Element<'_, Message> = if let Some(mode) = prompt.mode {
+			let chip = container(text(mode).size(CONFIG.font.size).wrapping(Wrapping::None))
+				.padding([2, 6])
+				.style(move |_| container::Style {
+					text_color: Some(theme.selected_row_text),
+					background: Some(theme.selected_row.into()),
+					border: Border::default().rounded(radius(4.0)),
+					shadow: Shadow::default(),
+					snap: false,
+				});
+
+			container(row![chip, input].align_y(Alignment::Center))
+				.padding([0, 4])
+				.style(move |_| container::Style {
+					text_color: None,
+					background: Some(theme.input_background.into()),
+					border: Border::default(),
+					shadow: Shadow::default(),
+					snap: false,
+				})
+				.into()
+		} else {
+			input.into()
+		};
+
 		column![
-			text_input("", &self.search)
-				.id(self.text_input.clone())
-				.size(15)
-				.on_input(Message::Typed)
-				.padding(7)
-				.style(|_, _| {
-					text_input::Style {
-						background: DARK_PURPLE.into(),
-						border: Border::default(),
-						icon: Color::BLACK,
-						placeholder: DISABLED_TEXT_COLOR,
-						value: TEXT_COLOR,
-						selection: SELECTED_TEXT_COLOR,
-					}
-				}),
+			search_bar,
 			scrollable(scrollable_column)
+				.id(self.scrollable.clone())
+				.on_scroll(Message::Scrolled)
 				.direction(Direction::Vertical(
 					Scrollbar::default().scroller_width(7.0).margin(1.0)
 				))
-				.style(|_, _| scrollable::Style {
+				.style(move |_, _| scrollable::Style {
 					container: container::Style {
-						text_color: Some(TEXT_COLOR),
+						text_color: Some(theme.text),
 						background: Some(Color::TRANSPARENT.into()),
 						border: Border::default(),
 						shadow: Shadow::default(),
@@ -163,30 +611,30 @@ impl View {
 						background: Some(Color::TRANSPARENT.into()),
 						border: Border::default(),
 						scroller: scrollable::Scroller {
-							background: SCROLLBAR_PURPLE.into(),
+							background: theme.scrollbar.into(),
 							border: Border::default()
 								.width(0)
 								.rounded(radius(5.0))
-								.color(SCROLLBAR_PURPLE),
+								.color(theme.scrollbar),
 						}
 					},
 					horizontal_rail: Rail {
 						background: Some(Color::TRANSPARENT.into()),
 						border: Border::default(),
 						scroller: scrollable::Scroller {
-							background: SCROLLBAR_PURPLE.into(),
+							background: theme.scrollbar.into(),
 							border: Border::default()
 								.width(0)
 								.rounded(radius(5.0))
-								.color(SCROLLBAR_PURPLE),
+								.color(theme.scrollbar),
 						}
 					},
 					gap: Some(Color::WHITE.into()),
 					auto_scroll: scrollable::AutoScroll {
-						background: SCROLLBAR_PURPLE.into(),
+						background: theme.scrollbar.into(),
 						border: Border::default(),
 						shadow: Shadow::default(),
-						icon: SCROLLBAR_PURPLE.into(),
+						icon: theme.scrollbar.into(),
 					},
 				}),
 		]
@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::config::{create_data_directory, data_directory, expand_home, CONFIG};
+use crate::error::{report, Error};
+use crate::launcher::shell_quote;
+
+// printed between the alias and function listings so one shell start gives us both
+const FUNCTIONS_MARKER: &str = "--bansheefinder-functions--";
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ShellEntries {
+	pub aliases: HashMap<String, String>, // name to what it expands to
+	pub functions: Vec<String>,
+	pub timestamp: u64, // when these were read from the shell
+}
+
+fn cache_path() -> PathBuf {
+	data_directory().join("shell_entries.json")
+}
+
+fn user_shell() -> String {
+	CONFIG
+		.shell
+		.shell
+		.clone()
+		.or_else(|| std::env::var("SHELL").ok())
+		.unwrap_or(String::from("sh"))
+}
+
+// turns a single quoted shell word like 'ls -l'\''s' back into the text it stands for
+fn unquote(value: &str) -> String {
+	let mut output = String::new();
+	let mut chars = value.chars();
+	let mut quote = None;
+	while let Some(character) = chars.next() {
+		match (quote, character) {
+			(None, '\'' | '"') => quote = Some(character),
+			(Some(open), _) if open == character => quote = None,
+			(None | Some('"'), '\\') => output.extend(chars.next()),
+			_ => output.push(character),
+		}
+	}
+
+	output
+}
+
+// handles bash's `alias name='value'`, zsh's `name=value` and fish's `alias name 'value'`
+pub fn parse_aliases(output: &str) -> HashMap<String, String> {
+	output
+		.lines()
+		.filter_map(|line| {
+			let line = line.trim();
+			let line = line.strip_prefix("alias ").unwrap_or(line);
+			// fish values can contain =, so the name ends at whichever comes first
+			let (name, value) = line.split_once(['=', ' '])?;
+			if name.is_empty() || name.contains(char::is_whitespace) {
+				return None;
+			}
+
+			Some((unquote(name), unquote(value)))
+		})
+		.collect()
+}
+
+fn load_from_shell() -> Option<ShellEntries> {
+	let shell = user_shell();
+	let functions = match Path::new(&shell).file_name()?.to_str()? {
+		"bash" => "compgen -A function",
+		"zsh" => "print -l ${(k)functions}",
+		"fish" => "functions -n | string split ', '",
+		_ => "",
+	};
+
+	// aliases and functions usually live in the rc file, which only interactive shells read
+	let output = Command::new(&shell)
+		.args(["-i", "-c", &format!("alias; echo {}; {}", FUNCTIONS_MARKER, functions)])
+		.stdin(Stdio::null())
+		.stderr(Stdio::null())
+		.output()
+		.ok()?;
+
+	let output = String::from_utf8_lossy(&output.stdout);
+	let (aliases, functions) = output.split_once(FUNCTIONS_MARKER).unwrap_or((&output, ""));
+	Some(ShellEntries {
+		aliases: parse_aliases(aliases),
+		functions: functions
+			.split_whitespace()
+			.filter(|function| !function.starts_with('_') && !function.starts_with('-'))
+			.map(String::from)
+			.collect(),
+		timestamp: chrono::Local::now().timestamp() as u64,
+	})
+}
+
+fn load_from_file(path: &str) -> ShellEntries {
+	let contents = std::fs::read_to_string(expand_home(path)).unwrap_or_default();
+	let aliases = contents
+		.lines()
+		.filter(|line| line.trim_start().starts_with("alias "))
+		.collect::<Vec<&str>>()
+		.join("\n");
+
+	ShellEntries {
+		aliases: parse_aliases(&aliases),
+		functions: Vec::new(),
+		timestamp: chrono::Local::now().timestamp() as u64,
+	}
+}
+
+fn read_cache() -> Option<ShellEntries> {
+	std::fs::read_to_string(cache_path())
+		.ok()
+		.and_then(|contents| serde_json::from_str::<ShellEntries>(&contents).ok())
+}
+
+// what is known without starting a shell, which can take a while, so an outdated cache is used as
+// it is until refresh_shell_entries has brought it up to date
+pub fn get_shell_entries() -> ShellEntries {
+	let config = &CONFIG.shell;
+	if !config.import_aliases {
+		return ShellEntries::default();
+	}
+
+	if let Some(path) = config.alias_file.as_ref() {
+		return load_from_file(path);
+	}
+
+	read_cache().unwrap_or_default()
+}
+
+pub fn needs_refresh() -> bool {
+	let config = &CONFIG.shell;
+	if !config.import_aliases || config.alias_file.is_some() {
+		return false;
+	}
+
+	let now = chrono::Local::now().timestamp() as u64;
+	read_cache().is_none_or(|cached| now.saturating_sub(cached.timestamp) >= config.cache_seconds)
+}
+
+// starts the user's shell to read its aliases and functions, meant to run in the background
+pub fn refresh_shell_entries() -> Option<ShellEntries> {
+	let entries = load_from_shell()?;
+
+	// the finder can exit halfway through, so the cache is replaced in one go
+	let partial = cache_path().with_extension("json.partial");
+	if let Err(error) = serde_json::to_string(&entries)
+		.map_err(std::io::Error::other)
+		.and_then(|contents| create_data_directory().map(|_| contents))
+		.and_then(|contents| std::fs::write(&partial, contents))
+		.and_then(|_| std::fs::rename(&partial, cache_path()))
+	{
+		report(Error::Write(cache_path(), error));
+	}
+
+	Some(entries)
+}
+
+fn on_path(name: &str) -> bool {
+	std::env::var("PATH")
+		.unwrap_or_default()
+		.split(':')
+		.any(|directory| Path::new(directory).join(name).is_file())
+}
+
+// scripts from the extra directories that the launch command would not find on its own
+fn find_script(name: &str) -> Option<PathBuf> {
+	if on_path(name) {
+		return None;
+	}
+
+	CONFIG
+		.shell
+		.script_directories
+		.iter()
+		.map(|directory| expand_home(directory).join(name))
+		.find(|path| path.is_file())
+}
+
+// rewrites the first word of a command so it can run outside of the user's shell: aliases are
+// expanded and run by that shell, functions run through it interactively and scripts get their
+// full path
+pub fn expand_command(command: &str) -> String {
+	let (name, arguments) = command.split_once(' ').unwrap_or((command, ""));
+	if name.is_empty() || on_path(name) {
+		return command.to_string();
+	}
+
+	if let Some(path) = find_script(name) {
+		return format!("{} {}", shell_quote(&path.to_string_lossy()), arguments);
+	}
+
+	// before the first refresh there is nothing cached yet, the launch waits for the shell then
+	let entries = if needs_refresh() && read_cache().is_none() {
+		refresh_shell_entries().unwrap_or_default()
+	} else {
+		get_shell_entries()
+	};
+
+	// alias bodies are written for the shell they were read from, sh may not understand them
+	if let Some(expansion) = entries.aliases.get(name) {
+		format!(
+			"{} -c {}",
+			shell_quote(&user_shell()),
+			shell_quote(&format!("{} {}", expansion, arguments))
+		)
+	} else if entries.functions.iter().any(|function| function == name) {
+		format!("{} -i -c {}", shell_quote(&user_shell()), shell_quote(command))
+	} else {
+		command.to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn alias<'a>(aliases: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+		aliases.get(name).map(String::as_str)
+	}
+
+	#[test]
+	fn parses_bash_aliases() {
+		let aliases = parse_aliases(concat!(
+			"alias ll='ls -l'\n",
+			"alias grep='grep --color=auto'\n",
+			"alias say='echo '\\''hi'\\'''\n",
+		));
+
+		assert_eq!(aliases.len(), 3);
+		assert_eq!(alias(&aliases, "ll"), Some("ls -l"));
+		assert_eq!(alias(&aliases, "grep"), Some("grep --color=auto"));
+		assert_eq!(alias(&aliases, "say"), Some("echo 'hi'"));
+	}
+
+	#[test]
+	fn parses_zsh_aliases() {
+		let aliases = parse_aliases(concat!("g=git\n", "ll='ls -l'\n", "run-help=man\n"));
+
+		assert_eq!(aliases.len(), 3);
+		assert_eq!(alias(&aliases, "g"), Some("git"));
+		assert_eq!(alias(&aliases, "ll"), Some("ls -l"));
+		assert_eq!(alias(&aliases, "run-help"), Some("man"));
+	}
+
+	#[test]
+	fn parses_fish_aliases() {
+		let aliases = parse_aliases(concat!(
+			"alias g 'git --x=y'\n",
+			"alias ll 'ls -l'\n",
+			"alias vi nvim\n",
+		));
+
+		assert_eq!(aliases.len(), 3);
+		assert_eq!(alias(&aliases, "g"), Some("git --x=y"));
+		assert_eq!(alias(&aliases, "ll"), Some("ls -l"));
+		assert_eq!(alias(&aliases, "vi"), Some("nvim"));
+	}
+
+	#[test]
+	fn skips_lines_that_are_not_aliases() {
+		assert!(parse_aliases("\nnothing\n=value\n").is_empty());
+	}
+}
@@ -1,37 +1,344 @@
-use iced::Color;
-
-pub const SCROLLBAR_PURPLE: Color = Color::from_rgb(
-	0x28 as f32 / 255.0,
-	0x1A as f32 / 255.0,
-	0x29 as f32 / 255.0,
-);
-
-pub const DARK_PURPLE: Color = Color::from_rgb(
-	0x1E as f32 / 255.0,
-	0x12 as f32 / 255.0,
-	0x1E as f32 / 255.0,
-);
-
-pub const TEXT_COLOR: Color = Color::from_rgb(
-	0xD3 as f32 / 255.0,
-	0xC6 as f32 / 255.0,
-	0xD3 as f32 / 255.0,
-);
-
-pub const DISABLED_TEXT_COLOR: Color = Color::from_rgb(
-	0xB7 as f32 / 255.0,
-	0xAC as f32 / 255.0,
-	0xB7 as f32 / 255.0,
-);
-
-pub const SELECTED_TEXT_COLOR: Color = Color::from_rgb(
-	0x73 as f32 / 255.0,
-	0x5E as f32 / 255.0,
-	0x6C as f32 / 255.0,
-);
-
-pub const LIGHT_PURPLE: Color = Color::from_rgb(
-	0x38 as f32 / 255.0,
-	0x26 as f32 / 255.0,
-	0x3F as f32 / 255.0,
-);
+use iced::{
+	font::{Family, Stretch, Style, Weight},
+	theme::Mode,
+	Color, Font,
+};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::{FontWeight, CONFIG};
+use crate::error::{report, Error};
+
+pub const ICON_FONT: Font = Font::with_name("Material Icons");
+
+// the font size every other text size was picked for
+const DEFAULT_FONT_SIZE: f32 = 9.0;
+
+// all of them are built in so the weight can be changed without installing anything
+const NOTO_SANS: [(FontWeight, bool, &[u8]); 18] = [
+	(
+		FontWeight::Thin,
+		false,
+		include_bytes!("../fonts/NotoSans-Thin.ttf"),
+	),
+	(
+		FontWeight::Thin,
+		true,
+		include_bytes!("../fonts/NotoSans-ThinItalic.ttf"),
+	),
+	(
+		FontWeight::ExtraLight,
+		false,
+		include_bytes!("../fonts/NotoSans-ExtraLight.ttf"),
+	),
+	(
+		FontWeight::ExtraLight,
+		true,
+		include_bytes!("../fonts/NotoSans-ExtraLightItalic.ttf"),
+	),
+	(
+		FontWeight::Light,
+		false,
+		include_bytes!("../fonts/NotoSans-Light.ttf"),
+	),
+	(
+		FontWeight::Light,
+		true,
+		include_bytes!("../fonts/NotoSans-LightItalic.ttf"),
+	),
+	(
+		FontWeight::Regular,
+		false,
+		include_bytes!("../fonts/NotoSans-Regular.ttf"),
+	),
+	(
+		FontWeight::Regular,
+		true,
+		include_bytes!("../fonts/NotoSans-Italic.ttf"),
+	),
+	(
+		FontWeight::Medium,
+		false,
+		include_bytes!("../fonts/NotoSans-Medium.ttf"),
+	),
+	(
+		FontWeight::Medium,
+		true,
+		include_bytes!("../fonts/NotoSans-MediumItalic.ttf"),
+	),
+	(
+		FontWeight::SemiBold,
+		false,
+		include_bytes!("../fonts/NotoSans-SemiBold.ttf"),
+	),
+	(
+		FontWeight::SemiBold,
+		true,
+		include_bytes!("../fonts/NotoSans-SemiBoldItalic.ttf"),
+	),
+	(
+		FontWeight::Bold,
+		false,
+		include_bytes!("../fonts/NotoSans-Bold.ttf"),
+	),
+	(
+		FontWeight::Bold,
+		true,
+		include_bytes!("../fonts/NotoSans-BoldItalic.ttf"),
+	),
+	(
+		FontWeight::ExtraBold,
+		false,
+		include_bytes!("../fonts/NotoSans-ExtraBold.ttf"),
+	),
+	(
+		FontWeight::ExtraBold,
+		true,
+		include_bytes!("../fonts/NotoSans-ExtraBoldItalic.ttf"),
+	),
+	(
+		FontWeight::Black,
+		false,
+		include_bytes!("../fonts/NotoSans-Black.ttf"),
+	),
+	(
+		FontWeight::Black,
+		true,
+		include_bytes!("../fonts/NotoSans-BlackItalic.ttf"),
+	),
+];
+
+lazy_static! {
+	static ref THEME: Theme = load_theme(&CONFIG.theme.name);
+	static ref SYSTEM_LIGHT_THEME: Theme = load_theme(&CONFIG.theme.light);
+}
+
+// only ever changed when the theme follows the system preference
+static SYSTEM_IS_LIGHT: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct Theme {
+	#[serde(with = "hex_color")]
+	pub background: Color,
+	#[serde(with = "hex_color")]
+	pub border: Color,
+	#[serde(with = "hex_color")]
+	pub text: Color,
+	#[serde(with = "hex_color")]
+	pub input_background: Color,
+	#[serde(with = "hex_color")]
+	pub input_text: Color,
+	#[serde(with = "hex_color")]
+	pub placeholder: Color,
+	#[serde(with = "hex_color")]
+	pub highlight: Color, // selected text in the inputs
+	#[serde(with = "hex_color")]
+	pub row: Color,
+	#[serde(with = "hex_color")]
+	pub row_text: Color,
+	#[serde(with = "hex_color")]
+	pub selected_row: Color,
+	#[serde(with = "hex_color")]
+	pub selected_row_text: Color,
+	#[serde(with = "hex_color")]
+	pub hover_row: Color,
+	#[serde(with = "hex_color")]
+	pub subtitle: Color,
+	#[serde(with = "hex_color")]
+	pub scrollbar: Color,
+	#[serde(with = "hex_color")]
+	pub error: Color,
+}
+
+const PURPLE_THEME: Theme = Theme {
+	background: Color::from_rgb8(0x1E, 0x12, 0x1E),
+	border: Color::from_rgb8(0x38, 0x26, 0x3F),
+	text: Color::from_rgb8(0xB7, 0xAC, 0xB7),
+	input_background: Color::from_rgb8(0x1E, 0x12, 0x1E),
+	input_text: Color::from_rgb8(0xD3, 0xC6, 0xD3),
+	placeholder: Color::from_rgb8(0xB7, 0xAC, 0xB7),
+	highlight: Color::from_rgb8(0x73, 0x5E, 0x6C),
+	row: Color::from_rgb8(0x1E, 0x12, 0x1E),
+	row_text: Color::from_rgb8(0xB7, 0xAC, 0xB7),
+	selected_row: Color::from_rgb8(0x38, 0x26, 0x3F),
+	selected_row_text: Color::from_rgb8(0xD3, 0xC6, 0xD3),
+	hover_row: Color::from_rgb8(0x2B, 0x1C, 0x2E),
+	subtitle: Color::from_rgb8(0x8E, 0x7C, 0x8A),
+	scrollbar: Color::from_rgb8(0x28, 0x1A, 0x29),
+	error: Color::from_rgb8(0xE0, 0x6C, 0x75),
+};
+
+const LIGHT_THEME: Theme = Theme {
+	background: Color::from_rgb8(0xF6, 0xF1, 0xF6),
+	border: Color::from_rgb8(0xD5, 0xC4, 0xD8),
+	text: Color::from_rgb8(0x4A, 0x3C, 0x4B),
+	input_background: Color::from_rgb8(0xF6, 0xF1, 0xF6),
+	input_text: Color::from_rgb8(0x2A, 0x1E, 0x2B),
+	placeholder: Color::from_rgb8(0x7A, 0x6A, 0x78),
+	highlight: Color::from_rgb8(0xCD, 0xB6, 0xD3),
+	row: Color::from_rgb8(0xF6, 0xF1, 0xF6),
+	row_text: Color::from_rgb8(0x4A, 0x3C, 0x4B),
+	selected_row: Color::from_rgb8(0xE2, 0xD2, 0xE6),
+	selected_row_text: Color::from_rgb8(0x1E, 0x12, 0x1E),
+	hover_row: Color::from_rgb8(0xED, 0xE3, 0xEE),
+	subtitle: Color::from_rgb8(0x7A, 0x6A, 0x78),
+	scrollbar: Color::from_rgb8(0xD5, 0xC4, 0xD8),
+	error: Color::from_rgb8(0xB3, 0x26, 0x1E),
+};
+
+const HIGH_CONTRAST_THEME: Theme = Theme {
+	background: Color::BLACK,
+	border: Color::WHITE,
+	text: Color::WHITE,
+	input_background: Color::BLACK,
+	input_text: Color::WHITE,
+	placeholder: Color::from_rgb8(0xC8, 0xC8, 0xC8),
+	highlight: Color::from_rgb8(0x1C, 0x5F, 0xB8),
+	row: Color::BLACK,
+	row_text: Color::WHITE,
+	selected_row: Color::from_rgb8(0xFF, 0xD7, 0x00),
+	selected_row_text: Color::BLACK,
+	hover_row: Color::from_rgb8(0x33, 0x33, 0x33),
+	subtitle: Color::from_rgb8(0xC8, 0xC8, 0xC8),
+	scrollbar: Color::WHITE,
+	error: Color::from_rgb8(0xFF, 0x55, 0x55),
+};
+
+fn builtin_theme(name: &str) -> Option<Theme> {
+	match name {
+		"purple" => Some(PURPLE_THEME),
+		"light" => Some(LIGHT_THEME),
+		"high_contrast" => Some(HIGH_CONTRAST_THEME),
+		_ => None,
+	}
+}
+
+// custom themes only list the colors they change, everything else comes from their base theme
+fn load_theme(name: &str) -> Theme {
+	if let Some(theme) = builtin_theme(name) {
+		return theme;
+	}
+
+	let custom = if let Some(custom) = CONFIG.theme.custom.get(name) {
+		custom
+	} else {
+		report(Error::Config(format!("unknown theme {:?}", name)));
+		return PURPLE_THEME;
+	};
+
+	let base = custom
+		.get("base")
+		.and_then(|base| base.as_str())
+		.and_then(builtin_theme)
+		.unwrap_or(PURPLE_THEME);
+
+	let mut colors = toml::Table::try_from(base).unwrap();
+	colors.extend(
+		custom
+			.iter()
+			.filter(|(key, _)| key.as_str() != "base")
+			.map(|(key, value)| (key.clone(), value.clone())),
+	);
+
+	match colors.try_into::<Theme>() {
+		Ok(theme) => theme,
+		Err(error) => {
+			report(Error::Config(format!(
+				"theme {:?}: {}",
+				name,
+				error.message()
+			)));
+			base
+		}
+	}
+}
+
+pub fn theme() -> &'static Theme {
+	if CONFIG.theme.follow_system && SYSTEM_IS_LIGHT.load(Ordering::Relaxed) {
+		&SYSTEM_LIGHT_THEME
+	} else {
+		&THEME
+	}
+}
+
+// loads the themes up front, so problems with them are known as soon as the window opens
+pub fn load_themes() {
+	lazy_static::initialize(&THEME);
+	if CONFIG.theme.follow_system {
+		lazy_static::initialize(&SYSTEM_LIGHT_THEME);
+	}
+}
+
+pub fn set_system_mode(mode: Mode) {
+	SYSTEM_IS_LIGHT.store(mode == Mode::Light, Ordering::Relaxed);
+}
+
+pub fn font() -> Font {
+	Font {
+		family: Family::Name(&CONFIG.font.family),
+		weight: match CONFIG.font.weight {
+			FontWeight::Thin => Weight::Thin,
+			FontWeight::ExtraLight => Weight::ExtraLight,
+			FontWeight::Light => Weight::Light,
+			FontWeight::Regular => Weight::Normal,
+			FontWeight::Medium => Weight::Medium,
+			FontWeight::SemiBold => Weight::Semibold,
+			FontWeight::Bold => Weight::Bold,
+			FontWeight::ExtraBold => Weight::ExtraBold,
+			FontWeight::Black => Weight::Black,
+		},
+		stretch: Stretch::Normal,
+		style: if CONFIG.font.italic {
+			Style::Italic
+		} else {
+			Style::Normal
+		},
+	}
+}
+
+// the Noto Sans face matching the configured font, other families are loaded from the system
+pub fn bundled_font() -> Option<&'static [u8]> {
+	if CONFIG.font.family != "Noto Sans" {
+		return None;
+	}
+
+	NOTO_SANS
+		.iter()
+		.find(|(weight, italic, _)| *weight == CONFIG.font.weight && *italic == CONFIG.font.italic)
+		.map(|(_, _, bytes)| *bytes)
+}
+
+// text sizes and row heights grow with the configured font size
+pub fn font_scale() -> f32 {
+	CONFIG.font.size / DEFAULT_FONT_SIZE
+}
+
+// colors are written as "#rrggbb" or "#rrggbbaa" in the config file
+mod hex_color {
+	use iced::Color;
+	use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+		let [r, g, b, a] = color.into_rgba8();
+		serializer.serialize_str(&format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+		let string = String::deserialize(deserializer)?;
+		let hex = string.trim_start_matches('#');
+		let channel = |index: usize| {
+			hex.get(index * 2..index * 2 + 2)
+				.and_then(|channel| u8::from_str_radix(channel, 16).ok())
+		};
+
+		match (hex.len(), channel(0), channel(1), channel(2)) {
+			(6, Some(r), Some(g), Some(b)) => Ok(Color::from_rgb8(r, g, b)),
+			(8, Some(r), Some(g), Some(b)) => match channel(3) {
+				Some(a) => Ok(Color::from_rgba8(r, g, b, a as f32 / 255.0)),
+				None => Err(D::Error::custom(format!("invalid color {:?}", string))),
+			},
+			_ => Err(D::Error::custom(format!("invalid color {:?}", string))),
+		}
+	}
+}
@@ -3,7 +3,7 @@ use iced::{
 	Alignment, Border, Color, Element, Length, Padding, Task,
 };
 
-use crate::style::{DARK_PURPLE, DISABLED_TEXT_COLOR, SELECTED_TEXT_COLOR, TEXT_COLOR};
+use crate::style::{font_scale, theme};
 
 #[derive(Clone, Debug)]
 pub enum Message {
@@ -38,24 +38,29 @@ impl View {
 	}
 
 	pub fn view(&self) -> Element<'_, Message> {
+		let theme = theme();
 		column![
 			Space::new().height(Length::Fixed(5.0)),
-			container(text("Password").size(14).width(Length::Fill))
-				.width(Length::Fill)
-				.padding(Padding::default().left(7)), // [0, 0, 0, 7]
+			container(
+				text("Password")
+					.size(14.0 * font_scale())
+					.width(Length::Fill)
+			)
+			.width(Length::Fill)
+			.padding(Padding::default().left(7)), // [0, 0, 0, 7]
 			text_input("", &self.password)
 				.id(self.text_input.clone())
-				.size(15)
+				.size(15.0 * font_scale())
 				.secure(true)
 				.on_input(Message::Typed)
 				.padding(Padding::default().top(4).right(7).bottom(4).left(7)) // [4, 7, 4, 7]
-				.style(|_, _| text_input::Style {
-					background: DARK_PURPLE.into(),
+				.style(move |_, _| text_input::Style {
+					background: theme.input_background.into(),
 					border: Border::default(),
 					icon: Color::BLACK,
-					placeholder: DISABLED_TEXT_COLOR,
-					value: TEXT_COLOR,
-					selection: SELECTED_TEXT_COLOR,
+					placeholder: theme.placeholder,
+					value: theme.input_text,
+					selection: theme.highlight,
 				})
 		]
 		.padding(0)
@@ -0,0 +1,88 @@
+use std::cmp::Ordering;
+
+use crate::path_interpreter::{compare_program_frequency, ProgramFrequencyMap};
+
+// generated from the unicode emoji-test.txt, gemoji aliases and the unicode character names so the
+// picker works without any network access or system packages
+static TABLE: &str = include_str!("../data/symbols.tsv");
+
+pub struct Symbol {
+	pub glyph: &'static str,
+	pub name: &'static str,
+	pub keywords: &'static str,
+}
+
+impl Symbol {
+	pub fn label(&self) -> String {
+		format!("{}  {}", self.glyph, self.name)
+	}
+}
+
+pub fn get_symbols() -> Vec<Symbol> {
+	TABLE
+		.lines()
+		.filter(|line| !line.starts_with('#'))
+		.filter_map(|line| {
+			let mut columns = line.split('\t');
+			Some(Symbol {
+				glyph: columns.next()?,
+				name: columns.next()?,
+				keywords: columns.next().unwrap_or_default(),
+			})
+		})
+		.collect()
+}
+
+// lower is better, a match on the name beats one that only hits the keywords
+fn match_rank(symbol: &Symbol, words: &[String]) -> Option<u8> {
+	if words.iter().all(|word| symbol.name.contains(word.as_str())) {
+		if symbol.name.starts_with(words[0].as_str()) {
+			Some(0)
+		} else {
+			Some(1)
+		}
+	} else if words.iter().all(|word| {
+		symbol.name.contains(word.as_str()) || symbol.keywords.contains(word.as_str())
+	}) {
+		Some(2)
+	} else {
+		None
+	}
+}
+
+// every word of the search has to appear somewhere, symbols that were picked before come first
+pub fn search<'a>(
+	symbols: &'a [Symbol],
+	frequency: &ProgramFrequencyMap,
+	search: &str,
+	limit: usize,
+) -> Vec<&'a Symbol> {
+	let words = search
+		.to_lowercase()
+		.split_whitespace()
+		.map(String::from)
+		.collect::<Vec<String>>();
+
+	let mut output = symbols
+		.iter()
+		.filter_map(|symbol| {
+			if words.is_empty() {
+				Some((symbol, 0))
+			} else {
+				Some((symbol, match_rank(symbol, &words)?))
+			}
+		})
+		.collect::<Vec<(&Symbol, u8)>>();
+
+	output.sort_by(|(a, a_rank), (b, b_rank)| {
+		match (frequency.map.get(a.glyph), frequency.map.get(b.glyph)) {
+			(Some(a), Some(b)) => compare_program_frequency(b, a, frequency),
+			(Some(_), None) => Ordering::Less,
+			(None, Some(_)) => Ordering::Greater,
+			(None, None) => a_rank.cmp(b_rank),
+		}
+	});
+
+	output.truncate(limit);
+	output.into_iter().map(|(symbol, _)| symbol).collect()
+}
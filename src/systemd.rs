@@ -0,0 +1,193 @@
+use serde::Deserialize;
+use std::process::Command;
+
+use crate::autocomplete::{UnitAction, UnitScope};
+use crate::config::CONFIG;
+use crate::launcher::shell_quote;
+
+// one entry of `systemctl list-units --output=json`
+#[derive(Clone, Debug, Deserialize)]
+struct ListedUnit {
+	unit: String,
+	active: String,
+	sub: String,
+	#[serde(default)]
+	description: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Unit {
+	pub name: String,
+	pub description: String,
+	pub active: String, // active, inactive, failed, activating, ...
+	pub sub: String,    // the unit type specific state, e.g. running or exited
+	pub scope: UnitScope,
+}
+
+fn scope_flag(scope: UnitScope) -> &'static str {
+	match scope {
+		UnitScope::User => "--user",
+		UnitScope::System => "--system",
+	}
+}
+
+// a row that does not look like a unit is skipped instead of losing the whole list
+fn parse_units(json: &str, scope: UnitScope) -> Vec<Unit> {
+	let rows: Vec<serde_json::Value> = serde_json::from_str(json).unwrap_or_default();
+	rows.into_iter()
+		.filter_map(|row| serde_json::from_value::<ListedUnit>(row).ok())
+		.map(|unit| Unit {
+			name: unit.unit,
+			description: unit.description,
+			active: unit.active,
+			sub: unit.sub,
+			scope,
+		})
+		.collect()
+}
+
+fn list_scope(systemctl: &str, scope: UnitScope, unit_types: &[String]) -> Vec<Unit> {
+	let output = Command::new(systemctl)
+		.arg(scope_flag(scope))
+		.arg("list-units")
+		.arg("--all")
+		.arg("--output=json")
+		.arg(format!("--type={}", unit_types.join(",")))
+		.output();
+
+	match output {
+		Ok(output) if output.status.success() => {
+			parse_units(&String::from_utf8_lossy(&output.stdout), scope)
+		}
+		_ => Vec::new(),
+	}
+}
+
+// user units come first, those are the ones people usually mean to poke at
+pub fn get_units() -> Vec<Unit> {
+	let config = &CONFIG.systemd;
+	let mut units = list_scope(&config.systemctl, UnitScope::User, &config.unit_types);
+	if config.system_units {
+		units.extend(list_scope(
+			&config.systemctl,
+			UnitScope::System,
+			&config.unit_types,
+		));
+	}
+
+	units
+}
+
+// the command line for an action, without sudo for privileged system actions
+pub fn command(name: &str, scope: UnitScope, action: UnitAction) -> String {
+	let config = &CONFIG.systemd;
+	match action {
+		UnitAction::Start | UnitAction::Stop | UnitAction::Restart | UnitAction::Status => format!(
+			"{} {} {} {}",
+			config.systemctl,
+			scope_flag(scope),
+			action.label(),
+			shell_quote(name)
+		),
+		UnitAction::Journal => match scope {
+			UnitScope::User => {
+				format!("{} --user-unit {} -e", config.journalctl, shell_quote(name))
+			}
+			UnitScope::System => format!("{} --unit {} -e", config.journalctl, shell_quote(name)),
+		},
+	}
+}
+
+// status and journal are read in a terminal, the other actions run on their own
+pub fn terminal_command(name: &str, scope: UnitScope, action: UnitAction) -> String {
+	match action {
+		UnitAction::Status | UnitAction::Journal => {
+			format!(
+				"{} {}",
+				CONFIG.systemd.terminal,
+				command(name, scope, action)
+			)
+		}
+		_ => command(name, scope, action),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const FAKE_SYSTEMCTL: &str =
+		concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/fake-systemctl");
+
+	fn unit_types() -> Vec<String> {
+		vec![
+			String::from("service"),
+			String::from("timer"),
+			String::from("socket"),
+		]
+	}
+
+	fn names(units: &[Unit]) -> Vec<&str> {
+		units.iter().map(|unit| unit.name.as_str()).collect()
+	}
+
+	#[test]
+	fn lists_user_units() {
+		let units = list_scope(FAKE_SYSTEMCTL, UnitScope::User, &unit_types());
+
+		assert_eq!(
+			names(&units),
+			vec![
+				"pipewire.service",
+				"syncthing.service",
+				"backup.timer",
+				"pipewire.socket"
+			]
+		);
+		assert!(units.iter().all(|unit| unit.scope == UnitScope::User));
+
+		let syncthing = &units[1];
+		assert_eq!(syncthing.active, "failed");
+		assert_eq!(syncthing.sub, "failed");
+		assert_eq!(
+			syncthing.description,
+			"Syncthing - Open Source Continuous File Synchronization"
+		);
+	}
+
+	#[test]
+	fn lists_system_units() {
+		let units = list_scope(FAKE_SYSTEMCTL, UnitScope::System, &unit_types());
+
+		assert_eq!(
+			names(&units),
+			vec![
+				"NetworkManager.service",
+				"bluetooth.service",
+				"fstrim.timer"
+			]
+		);
+		assert!(units.iter().all(|unit| unit.scope == UnitScope::System));
+		assert_eq!(units[1].active, "inactive");
+		assert_eq!(units[1].sub, "dead");
+	}
+
+	#[test]
+	fn skips_malformed_rows() {
+		let units = parse_units(
+			include_str!("../tests/fixtures/systemctl_malformed_units.json"),
+			UnitScope::System,
+		);
+
+		// the row without a name and the one with a number for a name are dropped, a missing
+		// description is fine
+		assert_eq!(names(&units), vec!["sshd.service", "cups.service"]);
+		assert_eq!(units[1].description, "");
+	}
+
+	#[test]
+	fn lists_nothing_when_systemctl_fails() {
+		assert!(list_scope("/nonexistent/systemctl", UnitScope::User, &unit_types()).is_empty());
+		assert!(parse_units("Failed to connect to bus", UnitScope::User).is_empty());
+	}
+}